@@ -1,48 +1,119 @@
-use std::{fs, path::Path};
+use std::borrow::Cow;
+
+use tracing::debug;
+
+use crate::{
+    error::DayError,
+    solution::{Answer, Example, Solution},
+};
+
+pub struct Day1;
+
+const EXAMPLES: &[Example] = &[
+    Example {
+        path: concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/src/advent23/examples/day1-part1.txt"
+        ),
+        input: include_str!("examples/day1-part1.txt"),
+        part1: Some("142"),
+        part2: None,
+    },
+    Example {
+        path: concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/src/advent23/examples/day1-part2.txt"
+        ),
+        input: include_str!("examples/day1-part2.txt"),
+        part1: None,
+        part2: Some("281"),
+    },
+];
 
-pub fn run<P: AsRef<Path>>(path: P) -> i32 {
-    let data = match fs::read_to_string(path) {
-        Ok(data) => data,
-        Err(err) => panic!("Failed to load input file: {}", err.to_string()),
-    };
-    return parser(data).iter().sum();
+impl Solution for Day1 {
+    type Parsed = Document;
+
+    fn name(&self) -> &'static str {
+        "Trebuchet?!"
+    }
+
+    fn day(&self) -> u32 {
+        1
+    }
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        parse(input)
+    }
+
+    fn part1(&self, document: &Self::Parsed) -> Result<Answer, DayError> {
+        part1(document).map(Answer::from)
+    }
+
+    fn part2(&self, document: &Self::Parsed) -> Result<Answer, DayError> {
+        part2(document).map(Answer::from)
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        EXAMPLES
+    }
 }
 
-fn parser(input: String) -> Vec<i32> {
-    let split = input.split('\n');
-    split
-        .into_iter()
-        .filter(|&line| line.ne(""))
-        .map(parse_digit_pair)
+/// The non-empty lines of the calibration document, which are ASCII only.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Document {
+    lines: Vec<String>,
+}
+
+/// One calibration line per line, made of ASCII characters.
+pub fn parse(input: &str) -> Result<Document, DayError> {
+    let lines: Vec<String> = input
+        .split('\n')
+        .filter(|&line| !line.is_empty())
+        .map(
+            |line| match line.char_indices().find(|(_, c)| !c.is_ascii()) {
+                Some((i, c)) => Err(DayError::unexpected(
+                    &line[i..i + c.len_utf8()],
+                    "ASCII letters and digits",
+                )),
+                None => Ok(line.to_string()),
+            },
+        )
+        .collect::<Result<_, _>>()?;
+    debug!(lines = lines.len(), "parsed calibration document");
+    Ok(Document { lines })
+}
+
+/// Sum of the calibration values made of the first and last digit.
+pub fn part1(document: &Document) -> Result<i32, DayError> {
+    Ok(parser(document).iter().sum())
+}
+
+/// Sum of the calibration values when spelled out digits count as well.
+pub fn part2(document: &Document) -> Result<i32, DayError> {
+    Ok(parser_with_words(document).iter().sum())
+}
+
+fn parser(document: &Document) -> Vec<i32> {
+    document
+        .lines
+        .iter()
+        .map(|line| parse_digit_pair(line))
         .map(pair_to_number)
         .collect()
 }
 
 fn parse_digit_pair(line: &str) -> (Option<u32>, Option<u32>) {
-    let mut numbers = line
-        .chars()
-        .into_iter()
-        .filter(|x| x.is_numeric())
-        .map(|x| x.to_digit(10).unwrap());
-
-    (numbers.next(), numbers.last())
-}
+    let mut numbers = line.chars().filter_map(|x| x.to_digit(10));
 
-pub fn run_part2<P: AsRef<Path>>(path: P) -> i32 {
-    let data = match fs::read_to_string(path) {
-        Ok(data) => data,
-        Err(err) => panic!("Failed to load input file: {}", err.to_string()),
-    };
-    return parser_with_words(data).iter().sum();
+    (numbers.next(), numbers.next_back())
 }
 
-fn parser_with_words(input: String) -> Vec<i32> {
-    let split = input.split('\n');
-    split
-        .into_iter()
-        .filter(|&line| line.ne(""))
-        .map(replace_words)
-        .map(parse_digit_pair)
+fn parser_with_words(document: &Document) -> Vec<i32> {
+    document
+        .lines
+        .iter()
+        .map(|line| replace_words(line))
+        .map(|line| parse_digit_pair(&line))
         .map(|pair| {
             let first = pair.0.unwrap_or(0);
             let last = pair.1.unwrap_or(first);
@@ -60,16 +131,16 @@ fn pair_to_number(pair: (Option<u32>, Option<u32>)) -> i32 {
     first as i32 * 10 + last as i32
 }
 
-const WORDS: [&'static str; 9] = [
+const WORDS: [&str; 9] = [
     "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
 ];
-const DIGITS: [&'static str; 9] = ["1", "2", "3", "4", "5", "6", "7", "8", "9"];
+const DIGITS: [&str; 9] = ["1", "2", "3", "4", "5", "6", "7", "8", "9"];
 
 // This was almost elegant, but wrong for case "eightwo"
-fn replace_words(line: &str) -> &str {
+fn replace_words(line: &str) -> Cow<'_, str> {
     let min_size = 3;
     if line.len() < min_size {
-        return line;
+        return Cow::Borrowed(line);
     }
 
     let max_size = 5;
@@ -80,7 +151,7 @@ fn replace_words(line: &str) -> &str {
     let mut chars = line.to_string();
     let mut buffer: String = chars[0..current_size].to_string();
     loop {
-        if buffer.len() == 0 {
+        if buffer.is_empty() {
             break;
         }
 
@@ -89,8 +160,8 @@ fn replace_words(line: &str) -> &str {
             .enumerate()
             .find(|(_, word)| buffer.eq(word));
 
-        if maybe_match.is_some() {
-            result.push_str(DIGITS[maybe_match.unwrap().0]);
+        if let Some((digit, _)) = maybe_match {
+            result.push_str(DIGITS[digit]);
             if current_size <= chars.len() {
                 chars = chars[1..].to_string();
                 current_size = min_size;
@@ -99,7 +170,7 @@ fn replace_words(line: &str) -> &str {
             continue;
         }
 
-        if chars.len() == 0 {
+        if chars.is_empty() {
             let removed = buffer.pop();
             match removed {
                 Some(removed) => {
@@ -126,21 +197,22 @@ fn replace_words(line: &str) -> &str {
         buffer = chars[0..current_size].to_string();
     }
 
-    result.leak()
+    Cow::Owned(result)
 }
 
+#[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_parser_oneliner() {
-        let res = parser("1abc2\n".to_string());
+        let res = parser(&parse("1abc2\n").unwrap());
         assert_eq!(res, vec![12])
     }
 
     #[test]
     fn test_parser_multiline() {
-        let res = parser("pqr3stu8vwx\na1b2c3d4e5f\ntreb7uchet".to_string());
+        let res = parser(&parse("pqr3stu8vwx\na1b2c3d4e5f\ntreb7uchet").unwrap());
         assert_eq!(res, vec![38, 15, 77])
     }
 
@@ -195,14 +267,16 @@ mod tests {
     #[test]
     fn test_parser_with_words_multiline() {
         let res = parser_with_words(
-            "two1nine
+            &parse(
+                "two1nine
 eightwothree
 abcone2threexyz
 xtwone3four
 4nineeightseven2
 zoneight234
-7pqrstsixteen"
-                .to_string(),
+7pqrstsixteen",
+            )
+            .unwrap(),
         );
         assert_eq!(res, vec![29, 83, 13, 24, 42, 14, 76])
     }
@@ -210,7 +284,8 @@ zoneight234
     #[test]
     fn test_parser_with_words_multiline_2() {
         let res = parser_with_words(
-            "
+            &parse(
+                "
 ninefive7cnxznfmcp6nine
 eight4one9x3nine
 eightnine4kgxhxx1ckrqlrn
@@ -226,8 +301,9 @@ v5jcblbstnvxk
 6s1
 6three1seven
 75xpmzmhqqphgtrblhkcdxczcvbmg
-"
-            .to_string(),
+",
+            )
+            .unwrap(),
         );
         assert_eq!(
             res,
@@ -238,15 +314,25 @@ v5jcblbstnvxk
     #[test]
     fn test_parser_with_words_multiline_with_sum() {
         let res = parser_with_words(
-            "two1nine
+            &parse(
+                "two1nine
 eightwothree
 abcone2threexyz
 xtwone3four
 4nineeightseven2
 zoneight234
-7pqrstsixteen"
-                .to_string(),
+7pqrstsixteen",
+            )
+            .unwrap(),
         );
         assert_eq!(res.iter().sum::<i32>(), 281)
     }
+
+    #[test]
+    fn test_parse_non_ascii() {
+        assert!(matches!(
+            parse("1abc2\nééa1\n"),
+            Err(DayError::UnexpectedToken { found, .. }) if found == "é"
+        ));
+    }
 }
@@ -1,65 +1,173 @@
-use std::{fs, path::Path};
+use tracing::debug;
+
+use crate::{
+    error::{parse_number, DayError},
+    solution::{Answer, Example, Query, Solution},
+};
+
+pub struct Day2;
+
+const EXAMPLES: &[Example] = &[Example {
+    path: concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/src/advent23/examples/day2.txt"
+    ),
+    input: include_str!("examples/day2.txt"),
+    part1: Some("8"),
+    part2: Some("2286"),
+}];
+
+const QUERIES: &[Query] = &[Query {
+    name: "show",
+    usage: "show <game id>",
+    help: "Print a game, whether it is possible and the fewest cubes it needs",
+}];
+
+impl Solution for Day2 {
+    type Parsed = Vec<Game>;
+
+    fn name(&self) -> &'static str {
+        "Cube Conundrum"
+    }
 
-pub fn run<P: AsRef<Path>>(path: P) -> i32 {
-    let data = match fs::read_to_string(path) {
-        Ok(data) => data,
-        Err(err) => panic!("Failed to load input file: {}", err.to_string()),
-    };
+    fn day(&self) -> u32 {
+        2
+    }
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        parse(input)
+    }
+
+    fn part1(&self, games: &Self::Parsed) -> Result<Answer, DayError> {
+        part1(games).map(Answer::from)
+    }
+
+    fn part2(&self, games: &Self::Parsed) -> Result<Answer, DayError> {
+        part2(games).map(Answer::from)
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        EXAMPLES
+    }
+
+    fn queries(&self) -> &'static [Query] {
+        QUERIES
+    }
 
-    return game(data).iter().sum();
+    fn query(
+        &self,
+        games: &Self::Parsed,
+        query: &Query,
+        args: &[&str],
+    ) -> Result<String, DayError> {
+        let id = parse_number::<i32>(query.arg(args, 0)?)?;
+        let game = games
+            .iter()
+            .find(|game| game.id == id)
+            .ok_or_else(|| DayError::Query(format!("no game {}", id)))?;
+
+        let hands: Vec<String> = game.hands.iter().map(Hand::describe).collect();
+        let lowest = game.lowest_hand();
+        Ok(format!(
+            "Game {}: {}\npossible: {}, fewest cubes: {}, power {}",
+            game.id,
+            hands.join("; "),
+            if game.is_valid_game() { "yes" } else { "no" },
+            lowest.describe(),
+            lowest.clone().power()?
+        ))
+    }
 }
 
-fn game(input: String) -> Vec<i32> {
-    let split = input.split('\n');
-    split
-        .into_iter()
-        .filter(|&line| line.ne(""))
+/// One game per line, `Game <id>: <hand>; <hand>; ...`.
+pub fn parse(input: &str) -> Result<Vec<Game>, DayError> {
+    input
+        .split('\n')
+        .filter(|&line| !line.is_empty())
         .map(parse_game)
-        .filter(Game::is_valid_game)
-        .map(Game::get_id)
         .collect()
 }
 
-pub fn run_part2<P: AsRef<Path>>(path: P) -> i32 {
-    let data = match fs::read_to_string(path) {
-        Ok(data) => data,
-        Err(err) => panic!("Failed to load input file: {}", err.to_string()),
-    };
+/// Sum of the ids of the games possible with 12 red, 13 green and 14 blue
+/// cubes.
+pub fn part1(games: &[Game]) -> Result<i32, DayError> {
+    sum(game(games), "ids")
+}
 
-    return power_of_cubes(data).iter().sum();
+/// Sum of the powers of the smallest set of cubes each game needs.
+pub fn part2(games: &[Game]) -> Result<i32, DayError> {
+    sum(power_of_cubes(games)?, "powers")
 }
 
-fn power_of_cubes(input: String) -> Vec<i32> {
-    let split = input.split('\n');
-    split
+fn sum(values: Vec<i32>, what: &str) -> Result<i32, DayError> {
+    values
         .into_iter()
-        .filter(|&line| line.ne(""))
-        .map(parse_game)
+        .try_fold(0i32, |total, value| total.checked_add(value))
+        .ok_or_else(|| DayError::Invalid(format!("the sum of the {} overflows", what)))
+}
+
+fn game(games: &[Game]) -> Vec<i32> {
+    let ids: Vec<i32> = games
+        .iter()
+        .filter(|game| game.is_valid_game())
+        .map(Game::get_id)
+        .collect();
+    debug!(
+        games = games.len(),
+        filtered = games.len() - ids.len(),
+        "filtered out impossible games"
+    );
+    ids
+}
+
+fn power_of_cubes(games: &[Game]) -> Result<Vec<i32>, DayError> {
+    games
+        .iter()
         .map(Game::lowest_hand)
         .map(Hand::power)
         .collect()
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
-struct Game {
+pub struct Game {
     id: i32,
     hands: Vec<Hand>,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Default)]
-struct Hand {
+pub struct Hand {
     red: i32,
     green: i32,
     blue: i32,
 }
 
 impl Hand {
+    #[cfg(test)]
     fn new(red: i32, green: i32, blue: i32) -> Self {
         Self { red, green, blue }
     }
 
-    fn power(self) -> i32 {
-        self.red * self.green * self.blue
+    pub fn red(&self) -> i32 {
+        self.red
+    }
+
+    pub fn green(&self) -> i32 {
+        self.green
+    }
+
+    pub fn blue(&self) -> i32 {
+        self.blue
+    }
+
+    pub fn power(self) -> Result<i32, DayError> {
+        self.red
+            .checked_mul(self.green)
+            .and_then(|power| power.checked_mul(self.blue))
+            .ok_or_else(|| DayError::Invalid(format!("the power of {} overflows", self.describe())))
+    }
+
+    fn describe(&self) -> String {
+        format!("{} red, {} green, {} blue", self.red, self.green, self.blue)
     }
 }
 
@@ -69,18 +177,15 @@ const BLUE: i32 = 14;
 
 impl Game {
     // Valid when lt_eq then 12 red cubes, 13 green cubes, and 14 blue cubes
-    fn is_valid_game(&self) -> bool {
-        match self
-            .hands
+    pub fn is_valid_game(&self) -> bool {
+        self.hands
             .iter()
             .find(|hand| hand.red > RED || hand.green > GREEN || hand.blue > BLUE)
-        {
-            Some(_) => false,
-            None => true,
-        }
+            .is_none()
     }
 
-    fn lowest_hand(self) -> Hand {
+    /// The fewest cubes of each color that make every hand possible.
+    pub fn lowest_hand(&self) -> Hand {
         let mut lowest_hand = Hand::default();
         for hand in self.hands.iter() {
             lowest_hand.red = lowest_hand.red.max(hand.red);
@@ -91,83 +196,83 @@ impl Game {
         lowest_hand
     }
 
-    fn get_id(self) -> i32 {
+    pub fn get_id(&self) -> i32 {
         self.id
     }
+
+    pub fn hands(&self) -> &[Hand] {
+        &self.hands
+    }
 }
 
-fn parse_game(line: &str) -> Game {
-    match line.split(":").collect::<Vec<&str>>()[..] {
-        [id, hands] => Game {
-            id: parse_id(id),
-            hands: parse_hands(hands),
-        },
-        _ => panic!("Failed to parse game line"),
+fn parse_game(line: &str) -> Result<Game, DayError> {
+    match line.split(':').collect::<Vec<&str>>()[..] {
+        [id, hands] => Ok(Game {
+            id: parse_id(id)?,
+            hands: parse_hands(hands)?,
+        }),
+        _ => Err(DayError::unexpected(line, "`Game <id>: <hands>`")),
     }
 }
 
-fn parse_hands(hands_part: &str) -> Vec<Hand> {
-    hands_part
-        .trim()
-        .split(";")
-        .into_iter()
-        .map(parse_hand)
-        .collect()
+fn parse_hands(hands_part: &str) -> Result<Vec<Hand>, DayError> {
+    hands_part.trim().split(';').map(parse_hand).collect()
 }
 
-fn parse_hand(hand_part: &str) -> Hand {
+fn parse_hand(hand_part: &str) -> Result<Hand, DayError> {
     let mut hand = Hand {
         red: 0,
         green: 0,
         blue: 0,
     };
 
-    let parts = hand_part.trim().split(",");
+    let parts = hand_part.trim().split(',');
     for cubes in parts {
-        let split: Vec<&str> = cubes.trim().split(" ").collect();
+        let split: Vec<&str> = cubes.trim().split(' ').collect();
         if split.len() != 2 {
-            panic!("Failed to parse cubes")
+            return Err(DayError::unexpected(cubes.trim(), "`<count> <color>`"));
         }
-        let count: i32 = split[0].parse::<i32>().unwrap();
+        let count: i32 = parse_number(split[0])?;
 
         match split[1] {
             "green" => hand.green = count,
             "red" => hand.red = count,
             "blue" => hand.blue = count,
-            color => panic!("Unknown color {}", color),
+            color => return Err(DayError::unexpected(color, "`red`, `green` or `blue`")),
         }
     }
 
-    hand
+    Ok(hand)
 }
 
-const GAME_PREFIX: &'static str = "Game ";
+const GAME_PREFIX: &str = "Game ";
 
-fn parse_id(id_part: &str) -> i32 {
+fn parse_id(id_part: &str) -> Result<i32, DayError> {
     match id_part.strip_prefix(GAME_PREFIX) {
-        Some(id) => id.parse::<i32>().unwrap(),
-        None => panic!("Failed to parse game id"),
+        Some(id) => parse_number(id),
+        None => Err(DayError::unexpected(id_part, "`Game <id>`")),
     }
 }
 
+#[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_parse_hand() {
-        let res = parse_hand(" 2 green, 12 blue");
+        let res = parse_hand(" 2 green, 12 blue").unwrap();
         assert_eq!(res, Hand::new(0, 2, 12));
 
-        let res = parse_hand("6 red, 6 blue");
+        let res = parse_hand("6 red, 6 blue").unwrap();
         assert_eq!(res, Hand::new(6, 0, 6));
 
-        let res = parse_hand("8 blue, 5 green, 5 red");
+        let res = parse_hand("8 blue, 5 green, 5 red").unwrap();
         assert_eq!(res, Hand::new(5, 5, 8));
     }
 
     #[test]
     fn test_parse_hands() {
-        let res = parse_hands(" 2 green, 12 blue; 6 red, 6 blue; 8 blue, 5 green, 5 red");
+        let res = parse_hands(" 2 green, 12 blue; 6 red, 6 blue; 8 blue, 5 green, 5 red").unwrap();
         assert_eq!(
             res,
             vec![Hand::new(0, 2, 12), Hand::new(6, 0, 6), Hand::new(5, 5, 8)]
@@ -175,26 +280,39 @@ mod tests {
     }
     #[test]
     fn test_parse_id() {
-        let res = parse_id("Game 1");
+        let res = parse_id("Game 1").unwrap();
         assert_eq!(res, 1);
 
-        let res = parse_id("Game 7");
+        let res = parse_id("Game 7").unwrap();
         assert_eq!(res, 7);
 
-        let res = parse_id("Game 12");
+        let res = parse_id("Game 12").unwrap();
         assert_eq!(res, 12);
     }
 
     #[test]
-    #[should_panic]
-    fn test_parse_id_panic_1() {
-        parse_id("Game k");
+    fn test_parse_id_error_1() {
+        assert!(matches!(
+            parse_id("Game k"),
+            Err(DayError::BadNumber { .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_id_error_2() {
+        assert!(matches!(
+            parse_id("Game"),
+            Err(DayError::UnexpectedToken { .. })
+        ));
     }
 
     #[test]
-    #[should_panic]
-    fn test_parse_id_panic_2() {
-        parse_id("Game");
+    fn test_parse_hand_unknown_color() {
+        let err = parse_hand("3 purple").unwrap_err();
+        assert_eq!(
+            "unexpected `purple`, expected `red`, `green` or `blue`",
+            err.to_string()
+        );
     }
 
     #[test]
@@ -204,21 +322,21 @@ mod tests {
             hands: vec![Hand::new(12, 13, 14)],
         };
 
-        assert_eq!(true, game.is_valid_game());
+        assert!(game.is_valid_game());
 
         let game = Game {
             id: 1,
             hands: vec![Hand::new(2, 3, 4)],
         };
 
-        assert_eq!(true, game.is_valid_game());
+        assert!(game.is_valid_game());
 
         let game = Game {
             id: 1,
             hands: vec![Hand::new(2, 3, 4), Hand::new(8, 9, 10)],
         };
 
-        assert_eq!(true, game.is_valid_game());
+        assert!(game.is_valid_game());
     }
 
     #[test]
@@ -228,21 +346,21 @@ mod tests {
             hands: vec![Hand::new(21, 31, 41), Hand::new(1, 1, 1)],
         };
 
-        assert_eq!(false, game.is_valid_game());
+        assert!(!game.is_valid_game());
 
         let game = Game {
             id: 1,
             hands: vec![Hand::new(1, 1, 1), Hand::new(13, 14, 15)],
         };
 
-        assert_eq!(false, game.is_valid_game());
+        assert!(!game.is_valid_game());
 
         let game = Game {
             id: 1,
             hands: vec![Hand::new(1, 1, 1), Hand::new(1, 1, 16)],
         };
 
-        assert_eq!(false, game.is_valid_game());
+        assert!(!game.is_valid_game());
     }
 
     #[test]
@@ -265,12 +383,34 @@ mod tests {
     #[test]
     fn test_hand_power() {
         let hand = Hand::new(1, 1, 13);
-        assert_eq!(13, hand.power());
+        assert_eq!(13, hand.power().unwrap());
 
         let hand = Hand::new(5, 6, 2);
-        assert_eq!(60, hand.power());
+        assert_eq!(60, hand.power().unwrap());
 
         let hand = Hand::new(6, 2, 2);
-        assert_eq!(24, hand.power());
+        assert_eq!(24, hand.power().unwrap());
+
+        let hand = Hand::new(70000, 70000, 1);
+        assert!(matches!(hand.power(), Err(DayError::Invalid(_))));
+    }
+
+    #[test]
+    fn test_sum_overflow() {
+        let games = parse("Game 2147483647: 1 red\nGame 1: 1 red\n").unwrap();
+        assert!(matches!(part1(&games), Err(DayError::Invalid(_))));
+    }
+
+    #[test]
+    fn test_query() {
+        let games = parse(EXAMPLES[0].input).unwrap();
+        let res = Day2.query(&games, &QUERIES[0], &["3"]).unwrap();
+        assert_eq!(
+            "Game 3: 20 red, 8 green, 6 blue; 4 red, 13 green, 5 blue; 1 red, 5 green, 0 blue
+possible: no, fewest cubes: 20 red, 13 green, 6 blue, power 1560",
+            res
+        );
+        assert!(Day2.query(&games, &QUERIES[0], &["6"]).is_err());
+        assert!(Day2.query(&games, &QUERIES[0], &[]).is_err());
     }
 }
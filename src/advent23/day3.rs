@@ -1,34 +1,82 @@
-use std::{
-    collections::{HashMap, HashSet},
-    fs,
-    path::Path,
+use std::collections::{HashMap, HashSet};
+
+use tracing::debug;
+
+use crate::{
+    error::{parse_number, DayError},
+    solution::{Answer, Example, Solution},
 };
 
-pub fn run<P: AsRef<Path>>(path: P) -> i32 {
-    let data = match fs::read_to_string(path) {
-        Ok(data) => data,
-        Err(err) => panic!("Failed to load input file: {}", err.to_string()),
-    };
-    return lift(data).iter().sum();
+pub struct Day3;
+
+const EXAMPLES: &[Example] = &[Example {
+    path: concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/src/advent23/examples/day3.txt"
+    ),
+    input: include_str!("examples/day3.txt"),
+    part1: Some("4361"),
+    part2: Some("467835"),
+}];
+
+impl Solution for Day3 {
+    type Parsed = Schematic;
+
+    fn name(&self) -> &'static str {
+        "Gear Ratios"
+    }
+
+    fn day(&self) -> u32 {
+        3
+    }
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        parse(input)
+    }
+
+    fn part1(&self, schema: &Self::Parsed) -> Result<Answer, DayError> {
+        part1(schema).map(Answer::from)
+    }
+
+    fn part2(&self, schema: &Self::Parsed) -> Result<Answer, DayError> {
+        part2(schema).map(Answer::from)
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        EXAMPLES
+    }
+}
+
+/// The engine schematic, a rectangular grid of characters with at least one
+/// row and column.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Schematic {
+    rows: Vec<Vec<char>>,
+}
+
+/// Sum of the numbers adjacent to a symbol.
+pub fn part1(schematic: &Schematic) -> Result<i32, DayError> {
+    Ok(lift(schematic)?.iter().sum())
+}
+
+/// Sum of the gear ratios, the products of the two numbers next to a `*`.
+pub fn part2(schematic: &Schematic) -> Result<i64, DayError> {
+    Ok(gears(schematic)?.iter().sum())
 }
 
 // I'm not really happy with this since it's kinda messy, but it's single iteration.
-fn lift(input: String) -> Vec<i32> {
-    let schema = parse(input);
+fn lift(schematic: &Schematic) -> Result<Vec<i32>, DayError> {
+    let schema = &schematic.rows;
     let schema_len = schema.len() - 1;
 
     let mut result = Vec::new();
     let mut buffer = Vec::new();
-    for (r, row) in schema.clone().into_iter().enumerate() {
+    for (r, row) in schema.iter().enumerate() {
         let r: i32 = r as i32;
         let row_len = row.len() - 1;
-        let mut row_iter = row.clone().into_iter().enumerate();
+        let row_iter = row.clone().into_iter().enumerate();
         let mut is_part_number = false;
-        loop {
-            let (c, char) = match row_iter.next() {
-                Some(t) => t,
-                None => break,
-            };
+        for (c, char) in row_iter {
             let c: i32 = c as i32;
             if char.is_numeric() {
                 let r_bounder = |x: i32| x.max(0).min(schema_len as i32) as usize;
@@ -57,10 +105,7 @@ fn lift(input: String) -> Vec<i32> {
                 buffer.push(char);
             } else {
                 if !buffer.is_empty() && is_part_number {
-                    let id: i32 = match buffer.iter().cloned().collect::<String>().parse() {
-                        Ok(id) => id,
-                        Err(err) => panic!("We matched something we didn't want to: {}", err),
-                    };
+                    let id: i32 = parse_number(&buffer.iter().collect::<String>())?;
                     result.push(id);
                 }
                 buffer.clear();
@@ -69,24 +114,13 @@ fn lift(input: String) -> Vec<i32> {
         }
 
         if !buffer.is_empty() && is_part_number {
-            let id: i32 = match buffer.iter().cloned().collect::<String>().parse() {
-                Ok(id) => id,
-                Err(err) => panic!("We matched something we didn't want to: {}", err),
-            };
+            let id: i32 = parse_number(&buffer.iter().collect::<String>())?;
             result.push(id);
         }
         buffer.clear();
     }
 
-    return result;
-}
-
-pub fn run_part2<P: AsRef<Path>>(path: P) -> i64 {
-    let data = match fs::read_to_string(path) {
-        Ok(data) => data,
-        Err(err) => panic!("Failed to load input file: {}", err.to_string()),
-    };
-    return gears(data).iter().sum();
+    Ok(result)
 }
 
 #[derive(Eq, PartialEq, Hash, Clone, Debug)]
@@ -103,23 +137,19 @@ impl Coordinate {
 
 // I'm not really happy with this since it's kinda messy, but it's single iteration.
 // This is smacking my ass, cannot figure out where is the mistake
-fn gears(input: String) -> Vec<i64> {
-    let schema = parse(input);
+fn gears(schematic: &Schematic) -> Result<Vec<i64>, DayError> {
+    let schema = &schematic.rows;
     let schema_len = schema.len() - 1;
 
     let mut result: HashMap<Coordinate, Vec<i64>> = HashMap::new();
     let mut buffer = Vec::new();
-    for (r, row) in schema.clone().into_iter().enumerate() {
+    for (r, row) in schema.iter().enumerate() {
         let r: i32 = r as i32;
         let row_len = row.len() - 1;
-        let mut row_iter = row.clone().into_iter().enumerate();
+        let row_iter = row.clone().into_iter().enumerate();
         let mut has_gear_attached = false;
         let mut gear_positions = HashSet::new();
-        loop {
-            let (c, char) = match row_iter.next() {
-                Some(t) => t,
-                None => break,
-            };
+        for (c, char) in row_iter {
             let c: i32 = c as i32;
             if char.is_numeric() {
                 let r_bounder = |x: i32| x.max(0).min(schema_len as i32) as usize;
@@ -172,10 +202,7 @@ fn gears(input: String) -> Vec<i64> {
                 buffer.push(char);
             } else {
                 if !buffer.is_empty() && has_gear_attached {
-                    let id: i64 = match buffer.iter().cloned().collect::<String>().parse() {
-                        Ok(id) => id,
-                        Err(err) => panic!("We matched something we didn't want to: {}", err),
-                    };
+                    let id: i64 = parse_number(&buffer.iter().collect::<String>())?;
                     for position in gear_positions.clone().into_iter() {
                         let mut ids = match result.get(&position) {
                             Some(v) => v.clone(),
@@ -192,10 +219,7 @@ fn gears(input: String) -> Vec<i64> {
         }
 
         if !buffer.is_empty() && has_gear_attached {
-            let id: i64 = match buffer.iter().cloned().collect::<String>().parse() {
-                Ok(id) => id,
-                Err(err) => panic!("We matched something we didn't want to: {}", err),
-            };
+            let id: i64 = parse_number(&buffer.iter().collect::<String>())?;
 
             for position in gear_positions.into_iter() {
                 let mut ids = match result.get(&position) {
@@ -209,11 +233,11 @@ fn gears(input: String) -> Vec<i64> {
         buffer.clear();
     }
 
-    return result
+    Ok(result
         .into_iter()
         .filter(|(_, v)| v.len() == 2)
         .map(|(_, v)| v[0] * v[1])
-        .collect();
+        .collect())
 }
 
 fn is_magic_char(c: char) -> bool {
@@ -224,22 +248,42 @@ fn is_gear(c: char) -> bool {
     c == '*'
 }
 
-fn parse(input: String) -> Vec<Vec<char>> {
-    input
+/// One row of the schematic per line, all of the same width.
+pub fn parse(input: &str) -> Result<Schematic, DayError> {
+    let lines: Vec<&str> = input
         .trim()
-        .split("\n")
-        .filter(|line| line.to_owned().ne(""))
-        .map(|line| line.trim().chars().collect())
-        .collect()
+        .split('\n')
+        .filter(|line| !line.is_empty())
+        .map(|line| line.trim())
+        .collect();
+
+    // The neighbour lookups in `lift` and `gears` assume a rectangular grid.
+    let width = match lines.first() {
+        Some(line) => line.chars().count(),
+        None => return Err(DayError::missing("schematic")),
+    };
+    debug!(rows = lines.len(), width, "parsed schematic size");
+    if let Some(line) = lines.iter().find(|line| line.chars().count() != width) {
+        return Err(DayError::unexpected(
+            line,
+            format!("a row of {} characters", width),
+        ));
+    }
+
+    Ok(Schematic {
+        rows: lines.iter().map(|line| line.chars().collect()).collect(),
+    })
 }
 
+#[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_lift() {
         let res = lift(
-            "
+            &parse(
+                "
 467..114..
 ...*......
 ..35..633.
@@ -249,13 +293,16 @@ mod tests {
 ..592.....
 ......755.
 ...$.*....
-.664.598.."
-                .to_string(),
-        );
+.664.598..",
+            )
+            .unwrap(),
+        )
+        .unwrap();
         assert_eq!(vec![467, 35, 633, 617, 592, 755, 664, 598], res);
 
         let res = lift(
-            "
+            &parse(
+                "
 467..114..
 ...*......
 ..35..633.
@@ -265,13 +312,16 @@ mod tests {
 ..592.....
 ......755.
 ...$.*....
-.664.598.."
-                .to_string(),
-        );
-        assert_eq!(4361, res.iter().sum());
+.664.598..",
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(4361, res.iter().sum::<i32>());
 
         let res = lift(
-            "
+            &parse(
+                "
 467#.114..
 ..........
 .#35..633#
@@ -281,13 +331,16 @@ mod tests {
 ..592+....
 ......755.
 .&*$......
-.664..598#"
-                .to_string(),
-        );
+.664..598#",
+            )
+            .unwrap(),
+        )
+        .unwrap();
         assert_eq!(vec![467, 35, 633, 617, 592, 755, 664, 598], res);
 
         let res = lift(
-            "
+            &parse(
+                "
 467#...4#.
 ..........
 .#35..633#
@@ -297,13 +350,16 @@ mod tests {
 ..592+....
 ......755.
 .&*$......
-.664..598#"
-                .to_string(),
-        );
+.664..598#",
+            )
+            .unwrap(),
+        )
+        .unwrap();
         assert_eq!(vec![467, 4, 35, 633, 617, 592, 755, 664, 598], res);
 
         let res = lift(
-            "
+            &parse(
+                "
 467#....#4
 ..........
 .#35..633#
@@ -313,16 +369,19 @@ mod tests {
 ..592+....
 ......755.
 .&*$......
-.664..598#"
-                .to_string(),
-        );
+.664..598#",
+            )
+            .unwrap(),
+        )
+        .unwrap();
         assert_eq!(vec![467, 4, 35, 633, 617, 592, 755, 664, 598], res);
     }
 
     #[test]
     fn test_gears() {
         let mut res = gears(
-            "
+            &parse(
+                "
 467..114..
 ....*.....
 ..35...140
@@ -332,11 +391,22 @@ mod tests {
 ..592...&.
 ......755.
 ...$.&....
-.664.598.."
-                .to_string(),
-        );
+.664.598..",
+            )
+            .unwrap(),
+        )
+        .unwrap();
 
         res.sort();
         assert_eq!(vec![114 * 35, 925 * 58, 140 * 925], res);
     }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(matches!(parse("\n\n"), Err(DayError::MissingSection(_))));
+        assert!(matches!(
+            parse("1*\n2\n"),
+            Err(DayError::UnexpectedToken { found, .. }) if found == "2"
+        ));
+    }
 }
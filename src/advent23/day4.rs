@@ -1,63 +1,160 @@
 use core::fmt;
-use std::{fs, path::Path};
 
-pub fn run<P: AsRef<Path>>(path: P) -> i32 {
-    let data = match fs::read_to_string(path) {
-        Ok(data) => data,
-        Err(err) => panic!("Failed to load input file: {}", err.to_string()),
-    };
-    return cards(data).iter().sum();
+use tracing::debug;
+
+use crate::{
+    error::{parse_number, DayError},
+    solution::{Answer, Example, Query, Solution},
+};
+
+pub struct Day4;
+
+const EXAMPLES: &[Example] = &[Example {
+    path: concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/src/advent23/examples/day4.txt"
+    ),
+    input: include_str!("examples/day4.txt"),
+    part1: Some("13"),
+    part2: Some("30"),
+}];
+
+const QUERIES: &[Query] = &[Query {
+    name: "show",
+    usage: "show <card id>",
+    help: "Print a card with its matching numbers and points",
+}];
+
+impl Solution for Day4 {
+    type Parsed = Vec<Card>;
+
+    fn name(&self) -> &'static str {
+        "Scratchcards"
+    }
+
+    fn day(&self) -> u32 {
+        4
+    }
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        parse(input)
+    }
+
+    fn part1(&self, cards: &Self::Parsed) -> Result<Answer, DayError> {
+        part1(cards).map(Answer::from)
+    }
+
+    fn part2(&self, cards: &Self::Parsed) -> Result<Answer, DayError> {
+        part2(cards).map(Answer::from)
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        EXAMPLES
+    }
+
+    fn queries(&self) -> &'static [Query] {
+        QUERIES
+    }
+
+    fn query(
+        &self,
+        cards: &Self::Parsed,
+        query: &Query,
+        args: &[&str],
+    ) -> Result<String, DayError> {
+        let id = parse_number::<i32>(query.arg(args, 0)?)?;
+        let card = cards
+            .iter()
+            .find(|card| card.id == id)
+            .ok_or_else(|| DayError::Query(format!("no card {}", id)))?;
+
+        let join = |numbers: &[i32]| {
+            let numbers: Vec<String> = numbers.iter().map(i32::to_string).collect();
+            numbers.join(" ")
+        };
+        let matching: Vec<i32> = card
+            .winning_numbers
+            .iter()
+            .copied()
+            .filter(|n| card.your_numbers.contains(n))
+            .collect();
+        Ok(format!(
+            "Card {}: {} | {}\nmatching: {}, points {}",
+            card.id,
+            join(&card.winning_numbers),
+            join(&card.your_numbers),
+            join(&matching),
+            card.get_points()?
+        ))
+    }
 }
 
-fn cards(input: String) -> Vec<i32> {
-    input
-        .split("\n")
-        .filter(|&line| line.ne(""))
+/// One card per line, ordered by id, which has to run from 1 to n.
+pub fn parse(input: &str) -> Result<Vec<Card>, DayError> {
+    let mut cards: Vec<Card> = input
+        .split('\n')
+        .filter(|&line| !line.is_empty())
         .map(parse_card)
-        .map(|card| Card::get_points(&card))
-        .collect()
+        .collect::<Result<_, _>>()?;
+    cards.sort_by_key(|a| a.id);
+
+    // `recursive_winning` indexes the pile by id, so ids have to be 1..=n.
+    for (i, card) in cards.iter().enumerate() {
+        if card.id != i as i32 + 1 {
+            return Err(DayError::Invalid(format!(
+                "card ids must run from 1 to n, Card {} is missing",
+                i + 1
+            )));
+        }
+    }
+    debug!(cards = cards.len(), "card ids run from 1 to n");
+
+    Ok(cards)
 }
 
-pub fn run_part2<P: AsRef<Path>>(path: P) -> i32 {
-    let data = match fs::read_to_string(path) {
-        Ok(data) => data,
-        Err(err) => panic!("Failed to load input file: {}", err.to_string()),
-    };
-    return pile(data);
+/// Total points of the cards.
+pub fn part1(cards: &[Card]) -> Result<i64, DayError> {
+    self::cards(cards)?
+        .into_iter()
+        .try_fold(0i64, |total, points| total.checked_add(points))
+        .ok_or_else(|| DayError::Invalid("the total points overflow".to_string()))
 }
 
-fn pile(input: String) -> i32 {
-    let mut cards: Vec<Card> = input
-        .split("\n")
-        .filter(|&line| line.ne(""))
-        .map(parse_card)
-        .collect();
-    cards.sort_by(|a, b| a.id.cmp(&b.id));
+/// Number of scratchcards once the won copies are counted.
+pub fn part2(cards: &[Card]) -> Result<i32, DayError> {
+    Ok(pile(cards))
+}
+
+fn cards(cards: &[Card]) -> Result<Vec<i64>, DayError> {
+    cards.iter().map(Card::get_points).collect()
+}
 
+fn pile(cards: &[Card]) -> i32 {
     let mut card_count = 0;
     cards
         .iter()
-        .for_each(|c| recursive_winning(c, &cards, &mut card_count));
+        .for_each(|c| recursive_winning(c, cards, &mut card_count));
 
     card_count
 }
 
 // this is huge waste of resources....
-fn recursive_winning(card: &Card, cards: &Vec<Card>, card_count: &mut i32) {
+fn recursive_winning(card: &Card, cards: &[Card], card_count: &mut i32) {
     *card_count += 1;
     match card.get_matches() {
         0 => {}
         x => {
-            let x: i32 = x as i32;
-            cards[card.id as usize..(card.id + x) as usize]
-                .into_iter()
-                .for_each(|c| recursive_winning(c, &cards, card_count));
+            let start = card.id as usize;
+            let end = (start + x as usize).min(cards.len());
+            cards[start..end]
+                .iter()
+                .for_each(|c| recursive_winning(c, cards, card_count));
         }
     };
 }
 
 #[derive(Eq, PartialEq, Clone)]
-struct Card {
+pub struct Card {
     id: i32,
     winning_numbers: Vec<i32>,
     your_numbers: Vec<i32>,
@@ -70,93 +167,108 @@ impl fmt::Debug for Card {
 }
 
 impl Card {
-    fn get_matches(&self) -> u32 {
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+
+    pub fn winning_numbers(&self) -> &[i32] {
+        &self.winning_numbers
+    }
+
+    pub fn your_numbers(&self) -> &[i32] {
+        &self.your_numbers
+    }
+
+    pub fn get_matches(&self) -> u32 {
         self.winning_numbers
             .iter()
             .filter(|x| self.your_numbers.contains(x))
             .count() as u32
     }
 
-    fn get_points(&self) -> i32 {
-        let base: i32 = 2;
+    /// 1 point for the first match, doubled for every further one.
+    pub fn get_points(&self) -> Result<i64, DayError> {
+        let base: i64 = 2;
         let matching_numbers = self.get_matches();
 
         match matching_numbers {
-            0 => 0,
-            1 => 1,
-            x => base.pow(x - 1),
+            0 => Ok(0),
+            x => base.checked_pow(x - 1).ok_or_else(|| {
+                DayError::Invalid(format!(
+                    "Card {} has {} matches, too many points to count",
+                    self.id, x
+                ))
+            }),
         }
     }
 }
 
-fn parse_card(line: &str) -> Card {
-    match line.split(":").collect::<Vec<&str>>()[..] {
+fn parse_card(line: &str) -> Result<Card, DayError> {
+    match line.split(':').collect::<Vec<&str>>()[..] {
         [id, hands] => {
             let split: Vec<&str> = hands.split(" | ").collect();
             if split.len() != 2 {
-                panic!("Invalid card")
+                return Err(DayError::unexpected(
+                    hands.trim(),
+                    "`<winning numbers> | <your numbers>`",
+                ));
             }
 
-            Card {
-                id: parse_id(id),
-                winning_numbers: parse_numbers(split[0]),
-                your_numbers: parse_numbers(split[1]),
-            }
+            Ok(Card {
+                id: parse_id(id)?,
+                winning_numbers: parse_numbers(split[0])?,
+                your_numbers: parse_numbers(split[1])?,
+            })
         }
-        _ => panic!("Failed to parse card line"),
+        _ => Err(DayError::unexpected(line, "`Card <id>: <numbers>`")),
     }
 }
 
-fn parse_numbers(numbers: &str) -> Vec<i32> {
+fn parse_numbers(numbers: &str) -> Result<Vec<i32>, DayError> {
     numbers
         .trim()
-        .split(" ")
-        .filter(|&n| n.ne(""))
-        .map(|n| match n.trim().parse::<i32>() {
-            Ok(x) => x,
-            Err(err) => panic!("Invalid number {}", err),
-        })
+        .split(' ')
+        .filter(|&n| !n.is_empty())
+        .map(parse_number)
         .collect()
 }
 
-const CARD_PREFIX: &'static str = "Card ";
-fn parse_id(id_part: &str) -> i32 {
+const CARD_PREFIX: &str = "Card ";
+fn parse_id(id_part: &str) -> Result<i32, DayError> {
     match id_part.strip_prefix(CARD_PREFIX) {
-        Some(id) => match id.trim().parse::<i32>() {
-            Ok(id) => id,
-            Err(err) => panic!("Failed to parse game id: {} {}", id, err),
-        },
-        None => panic!("Failed to parse game id"),
+        Some(id) => parse_number(id),
+        None => Err(DayError::unexpected(id_part, "`Card <id>`")),
     }
 }
 
+#[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_parse_id() {
-        let res = parse_id("Card 1");
+        let res = parse_id("Card 1").unwrap();
         assert_eq!(1, res);
 
-        let res = parse_id("Card 11");
+        let res = parse_id("Card 11").unwrap();
         assert_eq!(11, res);
 
-        let res = parse_id("Card 69");
+        let res = parse_id("Card 69").unwrap();
         assert_eq!(69, res);
     }
 
     #[test]
     fn test_parse_numbers() {
-        let res = parse_numbers("1 2 3 4 5");
+        let res = parse_numbers("1 2 3 4 5").unwrap();
         assert_eq!(vec![1, 2, 3, 4, 5], res);
 
-        let res = parse_numbers("83 86  6 31 17  9 48 53");
+        let res = parse_numbers("83 86  6 31 17  9 48 53").unwrap();
         assert_eq!(vec![83, 86, 6, 31, 17, 9, 48, 53], res);
     }
 
     #[test]
     fn test_parse_card() {
-        let res = parse_card("Card 1: 1 2 3 4 5 | 5 4 3 2 1");
+        let res = parse_card("Card 1: 1 2 3 4 5 | 5 4 3 2 1").unwrap();
         assert_eq!(
             Card {
                 id: 1,
@@ -175,7 +287,7 @@ mod tests {
             your_numbers: vec![61, 30, 68, 82, 17, 32, 24, 19],
         };
 
-        assert_eq!(2, card.get_points());
+        assert_eq!(2, card.get_points().unwrap());
 
         let card = Card {
             id: 1,
@@ -183,22 +295,33 @@ mod tests {
             your_numbers: vec![83, 86, 6, 31, 17, 9, 48, 53],
         };
 
-        assert_eq!(8, card.get_points());
+        assert_eq!(8, card.get_points().unwrap());
+
+        let numbers: Vec<i32> = (1..=64).collect();
+        let card = Card {
+            id: 7,
+            winning_numbers: numbers.clone(),
+            your_numbers: numbers,
+        };
+        assert!(matches!(card.get_points(), Err(DayError::Invalid(_))));
     }
 
     #[test]
     fn test_cards() {
         let res = cards(
-            "
+            &parse(
+                "
 Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
 Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
 Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
 Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
 Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
 Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
-"
-            .to_string(),
-        );
+",
+            )
+            .unwrap(),
+        )
+        .unwrap();
 
         assert_eq!(vec![8, 2, 2, 1, 0, 0], res);
     }
@@ -206,17 +329,47 @@ Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
     #[test]
     fn test_pile() {
         let res = pile(
-            "
+            &parse(
+                "
 Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
 Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
 Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
 Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
 Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
 Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
-"
-            .to_string(),
+",
+            )
+            .unwrap(),
         );
 
         assert_eq!(30, res);
     }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(matches!(
+            parse_card("Card 1: 1 2 3"),
+            Err(DayError::UnexpectedToken { .. })
+        ));
+        assert!(matches!(
+            parse_card("Card x: 1 | 2"),
+            Err(DayError::BadNumber { .. })
+        ));
+        assert!(matches!(
+            parse("Card 1: 1 | 2\nCard 3: 1 | 2"),
+            Err(DayError::Invalid(reason)) if reason == "card ids must run from 1 to n, Card 2 is missing"
+        ));
+    }
+
+    #[test]
+    fn test_query() {
+        let cards = parse(EXAMPLES[0].input).unwrap();
+        let res = Day4.query(&cards, &QUERIES[0], &["1"]).unwrap();
+        assert_eq!(
+            "Card 1: 41 48 83 86 17 | 83 86 6 31 17 9 48 53
+matching: 48 83 86 17, points 8",
+            res
+        );
+        assert!(Day4.query(&cards, &QUERIES[0], &["7"]).is_err());
+    }
 }
@@ -1,64 +1,185 @@
 use indicatif::ParallelProgressIterator;
 use rayon::prelude::*;
-use std::{collections::HashMap, fs, path::Path};
+use tracing::debug;
+
+use crate::{
+    cancel,
+    error::{parse_number, DayError},
+    progress,
+    solution::{Answer, Example, Query, Solution},
+};
+
+pub struct Day5;
+
+const EXAMPLES: &[Example] = &[Example {
+    path: concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/src/advent23/examples/day5.txt"
+    ),
+    input: include_str!("examples/day5.txt"),
+    part1: Some("35"),
+    part2: Some("46"),
+}];
+
+const QUERIES: &[Query] = &[
+    Query {
+        name: "show",
+        usage: "show seeds | show map <1-7 or name>",
+        help: "Print the seeds or the ranges of one map",
+    },
+    Query {
+        name: "trace",
+        usage: "trace <seed>",
+        help: "Follow a seed through every map down to its location",
+    },
+];
+
+impl Solution for Day5 {
+    type Parsed = Almanac;
+
+    fn name(&self) -> &'static str {
+        "If You Give A Seed A Fertilizer"
+    }
+
+    fn day(&self) -> u32 {
+        5
+    }
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        parse(input)
+    }
+
+    fn part1(&self, almanac: &Self::Parsed) -> Result<Answer, DayError> {
+        part1(almanac).map(Answer::from)
+    }
+
+    fn part2(&self, almanac: &Self::Parsed) -> Result<Answer, DayError> {
+        part2(almanac).map(Answer::from)
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        EXAMPLES
+    }
+
+    fn queries(&self) -> &'static [Query] {
+        QUERIES
+    }
+
+    fn query(&self, almanac: &Almanac, query: &Query, args: &[&str]) -> Result<String, DayError> {
+        match (query.name, query.arg(args, 0)?) {
+            ("show", "seeds") => Ok(join(almanac.seeds())),
+            ("show", "map") => {
+                let (name, map) = find_map(almanac, query.arg(args, 1)?)?;
+                let ranges: Vec<String> = map
+                    .ranges()
+                    .iter()
+                    .map(|(destination, source, length)| {
+                        format!("{} {} {}", destination, source, length)
+                    })
+                    .collect();
+                Ok(format!("{} map:\n{}", name, ranges.join("\n")))
+            }
+            ("trace", seed) => {
+                let mut value = parse_number::<i64>(seed)?;
+                let mut steps = vec![format!("seed {}", value)];
+                for (&name, map) in SECTIONS[1..].iter().zip(almanac.maps()) {
+                    value = map.get(value);
+                    let (_, stage) = name.split_once("-to-").unwrap_or(("", name));
+                    steps.push(format!("{} {}", stage, value));
+                }
+                Ok(steps.join(" -> "))
+            }
+            _ => Err(DayError::Query(format!("usage: {}", query.usage))),
+        }
+    }
+}
+
+fn join(numbers: &[i64]) -> String {
+    let numbers: Vec<String> = numbers.iter().map(i64::to_string).collect();
+    numbers.join(" ")
+}
 
-pub fn run<P: AsRef<Path>>(path: P) -> i64 {
-    let data = match fs::read_to_string(path) {
-        Ok(data) => data,
-        Err(err) => panic!("Failed to load input file: {}", err.to_string()),
+/// A map by its position, from 1, or by its section name.
+fn find_map<'a>(almanac: &'a Almanac, key: &str) -> Result<(&'static str, &'a SeedMap), DayError> {
+    let mut maps = SECTIONS[1..].iter().copied().zip(almanac.maps());
+    let found = match key.parse::<usize>() {
+        Ok(n) => maps.nth(n.wrapping_sub(1)),
+        Err(_) => maps.find(|(name, _)| *name == key),
     };
+    found
+        .ok_or_else(|| DayError::unexpected(key, "a map from 1 to 7 or a name like `seed-to-soil`"))
+}
 
-    return seeds(data);
+/// Lowest location of any of the seeds.
+pub fn part1(almanac: &Almanac) -> Result<i64, DayError> {
+    Ok(seeds(almanac))
 }
 
-fn seeds(input: String) -> i64 {
-    let almanac = parse(input);
+/// Lowest location when the seeds are read as start/length ranges.
+pub fn part2(almanac: &Almanac) -> Result<i64, DayError> {
+    if !almanac.seeds.len().is_multiple_of(2) {
+        return Err(DayError::Invalid(format!(
+            "seed ranges come in start/length pairs, found {} numbers",
+            almanac.seeds.len()
+        )));
+    }
+    for seed_range in almanac.seeds.chunks(2) {
+        let (start, length) = (seed_range[0], seed_range[1]);
+        if length < 0 || start.checked_add(length).is_none() {
+            return Err(DayError::Invalid(format!(
+                "the seed range `{} {}` is negative or overflows",
+                start, length
+            )));
+        }
+    }
+    seeds_range(almanac)
+}
 
+fn seeds(almanac: &Almanac) -> i64 {
     almanac
         .seeds
         .iter()
         .map(|&seed| almanac.seed_to_location(seed))
         .min()
-        .or(Some(0))
-        .unwrap()
-}
-
-pub fn run_part2<P: AsRef<Path>>(path: P) -> i64 {
-    let data = match fs::read_to_string(path) {
-        Ok(data) => data,
-        Err(err) => panic!("Failed to load input file: {}", err.to_string()),
-    };
-
-    return seeds_range(data);
+        .unwrap_or(0)
 }
 
-fn seeds_range(input: String) -> i64 {
-    let almanac = parse(input);
+/// Seeds handed to a worker at once, the token is checked between chunks.
+const CHUNK: i64 = 1 << 16;
+
+fn seeds_range(almanac: &Almanac) -> Result<i64, DayError> {
+    let token = cancel::current();
+    let mut locations = Vec::new();
+    for seed_range in almanac.seeds.chunks(2) {
+        let (start, length) = (seed_range[0], seed_range[1]);
+        let chunks = (start..start + length).step_by(CHUNK as usize);
+
+        let location = chunks
+            .par_bridge()
+            .progress_with(progress::bar((length as u64).div_ceil(CHUNK as u64)))
+            .map(|chunk| {
+                token.reason().is_none().then(|| {
+                    (chunk..(chunk + CHUNK).min(start + length))
+                        .map(|seed| almanac.seed_to_location(seed))
+                        .min()
+                })
+            })
+            .while_some()
+            .min();
+        // `while_some` stops early without saying so.
+        token.check()?;
+        locations.push(location.flatten());
+    }
 
-    let locations: Vec<_> = almanac
-        .seeds
-        .chunks(2)
-        .map(|seed_range| {
-            let range = seed_range[0]..seed_range[0] + seed_range[1];
-
-            range
-                .par_bridge()
-                .progress_count(seed_range[1] as u64)
-                .map(|seed| almanac.seed_to_location(seed))
-                .min()
-        })
-        .collect();
-
-    locations
+    Ok(locations
         .into_iter()
-        .map(|v| v.or(Some(0)).unwrap())
+        .map(|v| v.unwrap_or(0))
         .min()
-        .or(Some(0))
-        .unwrap()
+        .unwrap_or(0))
 }
 
 #[derive(PartialEq, Eq, Debug, Default)]
-struct Almanac {
+pub struct Almanac {
     seeds: Vec<i64>,
     seed_to_soil: SeedMap,
     soil_to_fertilizer: SeedMap,
@@ -70,7 +191,24 @@ struct Almanac {
 }
 
 impl Almanac {
-    fn seed_to_location(&self, seed: i64) -> i64 {
+    pub fn seeds(&self) -> &[i64] {
+        &self.seeds
+    }
+
+    /// The maps from seed to location, in the order they are applied.
+    pub fn maps(&self) -> [&SeedMap; 7] {
+        [
+            &self.seed_to_soil,
+            &self.soil_to_fertilizer,
+            &self.fertilizer_to_water,
+            &self.water_to_light,
+            &self.light_to_temperature,
+            &self.temperature_to_huminidity,
+            &self.huminidity_to_location,
+        ]
+    }
+
+    pub fn seed_to_location(&self, seed: i64) -> i64 {
         let soil = self.seed_to_soil.get(seed);
         let fertilizer = self.soil_to_fertilizer.get(soil);
         let water = self.fertilizer_to_water.get(fertilizer);
@@ -82,115 +220,139 @@ impl Almanac {
 }
 
 #[derive(PartialEq, Eq, Debug, Default)]
-struct SeedMap {
+pub struct SeedMap {
     map: Vec<(i64, i64, i64)>,
 }
 
 impl SeedMap {
+    #[cfg(test)]
     fn new(map: Vec<(i64, i64, i64)>) -> Self {
         Self { map }
     }
 }
 
 impl SeedMap {
-    fn get(&self, i: i64) -> i64 {
+    /// `(destination, source, length)` ranges.
+    pub fn ranges(&self) -> &[(i64, i64, i64)] {
+        &self.map
+    }
+
+    pub fn get(&self, i: i64) -> i64 {
+        // The ends of the ranges are checked when parsed, so nothing overflows.
         let maybe_match = self.map.iter().find(|&m| m.1 <= i && m.1 + m.2 > i);
         match maybe_match {
-            Some(m) => i + m.0 - m.1,
+            Some(m) => m.0 + (i - m.1),
             None => i,
         }
     }
 }
 
 // What is this monstrosity ?
-impl From<&str> for SeedMap {
-    fn from(value: &str) -> Self {
+impl TryFrom<&str> for SeedMap {
+    type Error = DayError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
         let map = value
             .trim()
-            .split("\n")
-            .into_iter()
+            .split('\n')
             .map(parse_map_line)
-            .collect();
+            .collect::<Result<_, _>>()?;
 
-        SeedMap { map }
+        Ok(SeedMap { map })
     }
 }
 
-fn parse_map_line(line: &str) -> (i64, i64, i64) {
+fn parse_map_line(line: &str) -> Result<(i64, i64, i64), DayError> {
     let numbers: Vec<i64> = line
-        .split(" ")
-        .filter(|&n| n.ne(""))
-        .into_iter()
-        .map(|n| n.trim().parse::<i64>().unwrap())
-        .collect();
+        .split(' ')
+        .filter(|&n| !n.is_empty())
+        .map(parse_number)
+        .collect::<Result<_, _>>()?;
 
     if numbers.len() != 3 {
-        panic!("Invalid map line {}", line);
+        return Err(DayError::unexpected(
+            line.trim(),
+            "`<destination> <source> <length>`",
+        ));
     }
 
-    (numbers[0], numbers[1], numbers[2])
+    let (destination, source, length) = (numbers[0], numbers[1], numbers[2]);
+    if destination.checked_add(length).is_none() || source.checked_add(length).is_none() {
+        return Err(DayError::Invalid(format!(
+            "the range `{}` overflows",
+            line.trim()
+        )));
+    }
+    Ok((destination, source, length))
 }
 
-fn parse_seeds(seeds: &str) -> Vec<i64> {
-    seeds
-        .trim()
-        .split(" ")
-        .into_iter()
-        .map(|n| n.parse::<i64>().unwrap())
-        .collect()
+fn parse_seeds(seeds: &str) -> Result<Vec<i64>, DayError> {
+    seeds.trim().split(' ').map(parse_number).collect()
 }
 
-fn parse(input: String) -> Almanac {
-    let chunks = input.trim().split("\n\n");
-    chunks.fold(Almanac::default(), |mut acc, chunk| {
-        let chunk_split: Vec<&str> = chunk.split(":").collect();
+const SECTIONS: [&str; 8] = [
+    "seeds",
+    "seed-to-soil",
+    "soil-to-fertilizer",
+    "fertilizer-to-water",
+    "water-to-light",
+    "light-to-temperature",
+    "temperature-to-humidity",
+    "humidity-to-location",
+];
+
+/// The seeds section followed by the seven maps, in any order.
+pub fn parse(input: &str) -> Result<Almanac, DayError> {
+    let mut almanac = Almanac::default();
+    let mut found = Vec::new();
+
+    for chunk in input.trim().split("\n\n") {
+        let chunk_split: Vec<&str> = chunk.split(':').collect();
         if chunk_split.len() != 2 {
-            panic!("Invalid chunk {}", chunk);
-        };
-
-        if chunk_split[0].starts_with("seeds") {
-            acc.seeds = parse_seeds(chunk_split[1]);
+            return Err(DayError::unexpected(chunk.trim(), "`<section>: <values>`"));
         };
 
-        if chunk_split[0].starts_with("seed-to-soil") {
-            acc.seed_to_soil = SeedMap::from(chunk_split[1]);
+        let header = chunk_split[0].trim();
+        let section = match SECTIONS.iter().find(|&&s| header.starts_with(s)) {
+            Some(&section) => section,
+            None => return Err(DayError::unexpected(header, "an almanac section header")),
         };
-
-        if chunk_split[0].starts_with("soil-to-fertilizer") {
-            acc.soil_to_fertilizer = SeedMap::from(chunk_split[1]);
-        };
-
-        if chunk_split[0].starts_with("fertilizer-to-water") {
-            acc.fertilizer_to_water = SeedMap::from(chunk_split[1]);
-        };
-
-        if chunk_split[0].starts_with("water-to-light") {
-            acc.water_to_light = SeedMap::from(chunk_split[1]);
-        };
-
-        if chunk_split[0].starts_with("light-to-temperature") {
-            acc.light_to_temperature = SeedMap::from(chunk_split[1]);
-        };
-
-        if chunk_split[0].starts_with("temperature-to-humidity") {
-            acc.temperature_to_huminidity = SeedMap::from(chunk_split[1]);
-        };
-
-        if chunk_split[0].starts_with("humidity-to-location") {
-            acc.huminidity_to_location = SeedMap::from(chunk_split[1]);
+        debug!(header, section, "matched section header");
+
+        match section {
+            "seeds" => almanac.seeds = parse_seeds(chunk_split[1])?,
+            "seed-to-soil" => almanac.seed_to_soil = SeedMap::try_from(chunk_split[1])?,
+            "soil-to-fertilizer" => almanac.soil_to_fertilizer = SeedMap::try_from(chunk_split[1])?,
+            "fertilizer-to-water" => {
+                almanac.fertilizer_to_water = SeedMap::try_from(chunk_split[1])?
+            }
+            "water-to-light" => almanac.water_to_light = SeedMap::try_from(chunk_split[1])?,
+            "light-to-temperature" => {
+                almanac.light_to_temperature = SeedMap::try_from(chunk_split[1])?
+            }
+            "temperature-to-humidity" => {
+                almanac.temperature_to_huminidity = SeedMap::try_from(chunk_split[1])?
+            }
+            _ => almanac.huminidity_to_location = SeedMap::try_from(chunk_split[1])?,
         };
+        found.push(section);
+    }
 
-        acc
-    })
+    match SECTIONS.iter().find(|s| !found.contains(s)) {
+        Some(section) => Err(DayError::missing(*section)),
+        None => Ok(almanac),
+    }
 }
 
+#[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_seeds() {
         let res = seeds(
-            "
+            &parse(
+                "
 seeds: 79 14 55 13
 
 seed-to-soil map:
@@ -224,32 +386,59 @@ temperature-to-humidity map:
 humidity-to-location map:
 60 56 37
 56 93 4
-                        "
-            .to_string(),
+                        ",
+            )
+            .unwrap(),
         );
 
         assert_eq!(35, res);
     }
 
+    #[test]
+    fn test_query() {
+        let almanac = parse(EXAMPLES[0].input).unwrap();
+        let query = |name: &str, args: &[&str]| {
+            let query = QUERIES.iter().find(|q| q.name == name).unwrap();
+            Day5.query(&almanac, query, args)
+        };
+
+        assert_eq!("79 14 55 13", query("show", &["seeds"]).unwrap());
+        assert_eq!(
+            "seed-to-soil map:\n50 98 2\n52 50 48",
+            query("show", &["map", "1"]).unwrap()
+        );
+        assert_eq!(
+            query("show", &["map", "1"]).unwrap(),
+            query("show", &["map", "seed-to-soil"]).unwrap()
+        );
+        assert!(query("show", &["map", "8"]).is_err());
+        assert_eq!(
+            "seed 79 -> soil 81 -> fertilizer 81 -> water 81 -> light 74 -> temperature 78 -> humidity 78 -> location 82",
+            query("trace", &["79"]).unwrap()
+        );
+        assert!(query("trace", &[]).is_err());
+        assert!(query("trace", &["x"]).is_err());
+    }
+
     #[test]
     fn test_seedmap_from_string() {
-        let res = SeedMap::from("50 98 2");
+        let res = SeedMap::try_from("50 98 2").unwrap();
 
         assert_eq!(SeedMap::new(vec![(50, 98, 2)]), res);
     }
 
     #[test]
     fn test_seedmap_get() {
-        let res = SeedMap::from("52 50 48").get(1);
+        let res = SeedMap::try_from("52 50 48").unwrap().get(1);
         assert_eq!(res, 1);
 
-        let res = SeedMap::from("52 50 48").get(79);
+        let res = SeedMap::try_from("52 50 48").unwrap().get(79);
         assert_eq!(res, 81);
     }
 
     #[test]
     fn test_parse_seeds() {
-        let res = parse_seeds("50 98 2");
+        let res = parse_seeds("50 98 2").unwrap();
         assert_eq!(vec![50, 98, 2], res);
     }
 
@@ -277,9 +466,9 @@ temperature-to-humidity map:
 0 69 1
 
 humidity-to-location map:
-56 93 1"
-                .to_string(),
-        );
+56 93 1",
+        )
+        .unwrap();
 
         assert_eq!(
             Almanac {
@@ -331,9 +520,9 @@ temperature-to-humidity map:
 
 humidity-to-location map:
 60 56 37
-56 93 4"
-                .to_string(),
-        );
+56 93 4",
+        )
+        .unwrap();
 
         assert_eq!(81, almanac.seed_to_soil.get(79));
         assert_eq!(81, almanac.soil_to_fertilizer.get(81));
@@ -345,4 +534,39 @@ humidity-to-location map:
 
         assert_eq!(82, almanac.seed_to_location(79));
     }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(matches!(
+            parse_map_line("50 98"),
+            Err(DayError::UnexpectedToken { .. })
+        ));
+        assert!(matches!(
+            parse_seeds("79 x"),
+            Err(DayError::BadNumber { .. })
+        ));
+        assert!(matches!(
+            parse("seeds: 79 14\n\nseed-to-soil map:\n50 98 2"),
+            Err(DayError::MissingSection(section)) if section == "soil-to-fertilizer"
+        ));
+        assert!(matches!(
+            parse("seeds: 79 14\n\nseed-to-dirt map:\n50 98 2"),
+            Err(DayError::UnexpectedToken { .. })
+        ));
+    }
+
+    #[test]
+    fn test_overflow() {
+        assert!(matches!(
+            parse_map_line("9223372036854775807 0 2"),
+            Err(DayError::Invalid(_))
+        ));
+
+        let input =
+            EXAMPLES[0]
+                .input
+                .replacen("seeds: 79 14 55 13", "seeds: 9223372036854775807 2", 1);
+        let almanac = parse(&input).unwrap();
+        assert!(matches!(part2(&almanac), Err(DayError::Invalid(_))));
+    }
 }
@@ -1,5 +1,52 @@
+use crate::solution::{DynSolution, Versioned};
+
 pub mod day1;
 pub mod day2;
 pub mod day3;
 pub mod day4;
 pub mod day5;
+
+pub const YEAR: u32 = 2023;
+
+/// Every implemented day, ordered by day number, versioned by its source.
+pub static SOLUTIONS: &[&dyn DynSolution] = &[
+    &Versioned::new(day1::Day1, include_str!("day1.rs")),
+    &Versioned::new(day2::Day2, include_str!("day2.rs")),
+    &Versioned::new(day3::Day3, include_str!("day3.rs")),
+    &Versioned::new(day4::Day4, include_str!("day4.rs")),
+    &Versioned::new(day5::Day5, include_str!("day5.rs")),
+];
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::solution::Part;
+
+    #[test]
+    fn test_solutions_are_ordered_and_unique() {
+        let days: Vec<u32> = SOLUTIONS.iter().map(|s| s.day()).collect();
+        let mut sorted = days.clone();
+        sorted.sort_unstable();
+        sorted.dedup();
+        assert_eq!(sorted, days);
+    }
+
+    #[test]
+    fn test_examples() {
+        for solution in SOLUTIONS {
+            assert!(!solution.examples().is_empty(), "day {}", solution.day());
+            for example in solution.examples() {
+                assert!(Path::new(example.path).is_file(), "{}", example.path);
+                let parsed = solution.parse(example.input).unwrap();
+                for part in Part::ALL {
+                    if let Some(expected) = example.expected(part) {
+                        let res = solution.solve(part, parsed.as_ref()).unwrap();
+                        assert_eq!(expected, res.to_string(), "{}", example.path);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use std::{collections::BTreeMap, fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::solution::Part;
+
+pub const DEFAULT_ANSWERS_FILE: &str = "answers.toml";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct DayAnswers {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    part1: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    part2: Option<String>,
+}
+
+/// Accepted answers, stored as one `[YEAR.dayN]` table per day:
+///
+/// ```toml
+/// [2023.day1]
+/// part1 = "55488"
+/// part2 = "55614"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Answers {
+    days: BTreeMap<(u32, u32), DayAnswers>,
+}
+
+impl Answers {
+    /// Reads `path`, an absent file is an empty set of answers.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(content) => Self::parse(&content),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn parse(content: &str) -> io::Result<Self> {
+        let invalid = |key: &str| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid table `[{}]`, expected `[YEAR.dayN]`", key),
+            )
+        };
+        let years: BTreeMap<String, toml::Table> = toml::from_str(content)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+        let mut days = BTreeMap::new();
+        for (year_key, tables) in years {
+            let year = year_key.parse::<u32>().map_err(|_| invalid(&year_key))?;
+            for (key, answers) in tables {
+                let day = key
+                    .strip_prefix("day")
+                    .and_then(|day| day.parse::<u32>().ok())
+                    .ok_or_else(|| invalid(&format!("{}.{}", year_key, key)))?;
+                let answers = answers
+                    .try_into()
+                    .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+                days.insert((year, day), answers);
+            }
+        }
+
+        Ok(Self { days })
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_toml())
+    }
+
+    pub fn to_toml(&self) -> String {
+        // Keys are written in day order rather than `day1, day10, day2`.
+        self.days
+            .iter()
+            .map(|((year, day), answers)| {
+                let mut table = BTreeMap::new();
+                table.insert(format!("day{}", day), answers);
+                let mut year_table = BTreeMap::new();
+                year_table.insert(year.to_string(), table);
+                toml::to_string(&year_table).unwrap_or_default()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    pub fn get(&self, year: u32, day: u32, part: Part) -> Option<&str> {
+        let answers = self.days.get(&(year, day))?;
+        match part {
+            Part::One => answers.part1.as_deref(),
+            Part::Two => answers.part2.as_deref(),
+        }
+    }
+
+    pub fn set(&mut self, year: u32, day: u32, part: Part, answer: String) {
+        let answers = self.days.entry((year, day)).or_default();
+        match part {
+            Part::One => answers.part1 = Some(answer),
+            Part::Two => answers.part2 = Some(answer),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let res = Answers::parse(
+            "
+[2023.day1]
+part1 = \"55488\"
+
+[2023.day10]
+part2 = \"abc\"
+
+[2022.day1]
+part1 = \"7\"
+",
+        )
+        .unwrap();
+
+        assert_eq!(Some("55488"), res.get(2023, 1, Part::One));
+        assert_eq!(None, res.get(2023, 1, Part::Two));
+        assert_eq!(Some("abc"), res.get(2023, 10, Part::Two));
+        assert_eq!(None, res.get(2023, 2, Part::One));
+        assert_eq!(Some("7"), res.get(2022, 1, Part::One));
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(Answers::parse("[first]\npart1 = \"1\"").is_err());
+        assert!(Answers::parse("[day1]\npart1 = \"1\"").is_err());
+        assert!(Answers::parse("[2023.first]\npart1 = \"1\"").is_err());
+        assert!(Answers::parse("[2023.day1]\npart1 = ").is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut answers = Answers::default();
+        answers.set(2023, 10, Part::One, "7".to_string());
+        answers.set(2023, 2, Part::Two, "70924".to_string());
+        answers.set(2023, 2, Part::One, "2771".to_string());
+        answers.set(2022, 25, Part::One, "2=-1".to_string());
+
+        let res = answers.to_toml();
+        assert_eq!(
+            "[2022.day25]
+part1 = \"2=-1\"
+
+[2023.day2]
+part1 = \"2771\"
+part2 = \"70924\"
+
+[2023.day10]
+part1 = \"7\"
+",
+            res
+        );
+        assert_eq!(answers, Answers::parse(&res).unwrap());
+    }
+}
@@ -0,0 +1,185 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    error::DayError,
+    runner::caught,
+    solution::{DynSolution, Part},
+};
+
+/// Runs `f` once and returns its result together with the wall time it took.
+pub fn timed<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let res = f();
+    (res, start.elapsed())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub p95: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+
+        let mut sorted = samples.to_vec();
+        sorted.sort_unstable();
+        let n = sorted.len();
+
+        let median = if n.is_multiple_of(2) {
+            (sorted[n / 2 - 1] + sorted[n / 2]) / 2
+        } else {
+            sorted[n / 2]
+        };
+        // Nearest rank percentile.
+        let p95 = sorted[(n * 95).div_ceil(100) - 1];
+
+        Self {
+            min: sorted[0],
+            median,
+            mean: sorted.iter().sum::<Duration>() / n as u32,
+            p95,
+        }
+    }
+}
+
+/// Statistics of the parse step and of each part of one day.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DayStats {
+    pub parse: Stats,
+    pub parts: Vec<(Part, Stats)>,
+}
+
+impl DayStats {
+    /// Sum of the medians of every phase.
+    pub fn total(&self) -> Duration {
+        self.parts
+            .iter()
+            .fold(self.parse.median, |total, (_, stats)| total + stats.median)
+    }
+}
+
+/// Parses `input` and solves `parts` `warmup + iterations` times, the
+/// statistics cover the last `iterations` runs. The first error, or panic,
+/// stops it.
+pub fn bench_day(
+    solution: &dyn DynSolution,
+    input: &str,
+    parts: &[Part],
+    warmup: u32,
+    iterations: u32,
+) -> Result<DayStats, DayError> {
+    let mut parse_samples = Vec::new();
+    let mut solve_samples = vec![Vec::new(); parts.len()];
+    for run in 0..warmup + iterations {
+        let (parsed, parse_time) = timed(|| caught(|| solution.parse(input)));
+        let parsed = parsed?;
+        let measured = run >= warmup;
+        if measured {
+            parse_samples.push(parse_time);
+        }
+
+        for (i, &part) in parts.iter().enumerate() {
+            let (res, solve_time) = timed(|| caught(|| solution.solve(part, parsed.as_ref())));
+            res?;
+            if measured {
+                solve_samples[i].push(solve_time);
+            }
+        }
+    }
+
+    Ok(DayStats {
+        parse: Stats::from_samples(&parse_samples),
+        parts: parts
+            .iter()
+            .zip(&solve_samples)
+            .map(|(&part, samples)| (part, Stats::from_samples(samples)))
+            .collect(),
+    })
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    let nanos = duration.as_nanos();
+    if nanos < 1_000 {
+        format!("{}ns", nanos)
+    } else if nanos < 1_000_000 {
+        format!("{:.1}µs", nanos as f64 / 1e3)
+    } else if nanos < 1_000_000_000 {
+        format!("{:.2}ms", nanos as f64 / 1e6)
+    } else {
+        format!("{:.2}s", nanos as f64 / 1e9)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{advent23::day2::Day2, runner::tests::Panics, solution::Solution};
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    #[test]
+    fn test_stats_odd() {
+        let res = Stats::from_samples(&[ms(5), ms(1), ms(3)]);
+        assert_eq!(
+            Stats {
+                min: ms(1),
+                median: ms(3),
+                mean: ms(3),
+                p95: ms(5),
+            },
+            res
+        );
+    }
+
+    #[test]
+    fn test_stats_even_and_p95() {
+        let samples: Vec<Duration> = (1..=20).map(ms).collect();
+        let res = Stats::from_samples(&samples);
+        assert_eq!(ms(1), res.min);
+        assert_eq!(Duration::from_micros(10_500), res.median);
+        assert_eq!(Duration::from_micros(10_500), res.mean);
+        assert_eq!(ms(19), res.p95);
+    }
+
+    #[test]
+    fn test_stats_empty() {
+        assert_eq!(Stats::default(), Stats::from_samples(&[]));
+    }
+
+    #[test]
+    fn test_bench_day() {
+        let res = bench_day(
+            &Day2,
+            Solution::examples(&Day2)[0].input,
+            &[Part::Two],
+            1,
+            3,
+        )
+        .unwrap();
+        assert_eq!(1, res.parts.len());
+        assert_eq!(Part::Two, res.parts[0].0);
+        assert_eq!(res.parse.median + res.parts[0].1.median, res.total());
+
+        assert!(bench_day(&Day2, "Game 1: 3 purple\n", &Part::ALL, 0, 1).is_err());
+        assert!(matches!(
+            bench_day(&Panics, "", &[Part::One], 0, 1),
+            Err(DayError::Panicked(message)) if message == "no part 1"
+        ));
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!("999ns", format_duration(Duration::from_nanos(999)));
+        assert_eq!("1.5µs", format_duration(Duration::from_nanos(1_500)));
+        assert_eq!("2.25ms", format_duration(Duration::from_micros(2_250)));
+        assert_eq!("3.00s", format_duration(Duration::from_secs(3)));
+    }
+}
@@ -0,0 +1,177 @@
+use std::{
+    fs, io,
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::solution::Part;
+
+pub const DEFAULT_CACHE_FILE: &str = "cache.json";
+
+/// 64-bit FNV-1a, stable across runs and platforms unlike the std hashers.
+pub fn hash(input: &str) -> u64 {
+    input.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct Entry {
+    year: u32,
+    day: u32,
+    part: u8,
+    version: u32,
+    /// Hash of the input, in hex.
+    input: String,
+    answer: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct File {
+    entries: Vec<Entry>,
+}
+
+/// Answers computed earlier for `year`, keyed by day, part, solution version
+/// and input hash. Shared between the threads running days.
+#[derive(Debug)]
+pub struct Cache {
+    year: u32,
+    entries: Mutex<Vec<Entry>>,
+    changed: AtomicBool,
+}
+
+impl Cache {
+    pub fn new(year: u32) -> Self {
+        Self {
+            year,
+            entries: Mutex::new(Vec::new()),
+            changed: AtomicBool::new(false),
+        }
+    }
+
+    /// Reads `path`, an absent file is an empty cache.
+    pub fn load(path: &Path, year: u32) -> io::Result<Self> {
+        let file: File = match fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => File::default(),
+            Err(err) => return Err(err),
+        };
+        let cache = Self::new(year);
+        *cache.entries.lock().unwrap() = file.entries;
+        Ok(cache)
+    }
+
+    /// Writes the cache back to `path` if anything was added.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if !self.changed.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        let file = File {
+            entries: self.entries.lock().unwrap().clone(),
+        };
+        let mut content = serde_json::to_string_pretty(&file)?;
+        content.push('\n');
+        fs::write(path, content)
+    }
+
+    pub fn get(&self, day: u32, part: Part, version: u32, input: u64) -> Option<String> {
+        let input = format!("{:016x}", input);
+        self.entries
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|e| {
+                e.year == self.year
+                    && e.day == day
+                    && e.part == part.number()
+                    && e.version == version
+                    && e.input == input
+            })
+            .map(|e| e.answer.clone())
+    }
+
+    /// Stores `answer`, replacing the one computed by any other version for
+    /// the same input.
+    pub fn insert(&self, day: u32, part: Part, version: u32, input: u64, answer: String) {
+        let entry = Entry {
+            year: self.year,
+            day,
+            part: part.number(),
+            version,
+            input: format!("{:016x}", input),
+            answer,
+        };
+
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|e| {
+            (e.year, e.day, e.part, &e.input) != (entry.year, entry.day, entry.part, &entry.input)
+        });
+        entries.push(entry);
+        self.changed.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Removes the cache at `path`, returns whether there was one.
+pub fn clear(path: &Path) -> io::Result<bool> {
+    match fs::remove_file(path) {
+        Ok(()) => Ok(true),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    #[test]
+    fn test_hash() {
+        assert_eq!(0xcbf2_9ce4_8422_2325, hash(""));
+        assert_eq!(0xaf63_dc4c_8601_ec8c, hash("a"));
+        assert_ne!(hash("1abc2\n"), hash("1abc3\n"));
+    }
+
+    #[test]
+    fn test_get_and_insert() {
+        let cache = Cache::new(2023);
+        assert_eq!(None, cache.get(1, Part::One, 1, 42));
+
+        cache.insert(1, Part::One, 1, 42, "142".to_string());
+        assert_eq!(Some("142".to_string()), cache.get(1, Part::One, 1, 42));
+        assert_eq!(None, cache.get(1, Part::Two, 1, 42));
+        assert_eq!(None, cache.get(1, Part::One, 1, 43));
+        assert_eq!(None, cache.get(1, Part::One, 2, 42));
+        assert_eq!(None, Cache::new(2022).get(1, Part::One, 1, 42));
+
+        cache.insert(1, Part::One, 2, 42, "143".to_string());
+        assert_eq!(None, cache.get(1, Part::One, 1, 42));
+        assert_eq!(Some("143".to_string()), cache.get(1, Part::One, 2, 42));
+    }
+
+    #[test]
+    fn test_save_load_and_clear() {
+        let path = env::temp_dir().join(format!("aoc-cache-{}.json", std::process::id()));
+        let cache = Cache::new(2023);
+        cache.insert(4, Part::Two, 1, 7, "6857330".to_string());
+        cache.save(&path).unwrap();
+
+        let res = Cache::load(&path, 2023).unwrap();
+        assert_eq!(Some("6857330".to_string()), res.get(4, Part::Two, 1, 7));
+
+        assert!(clear(&path).unwrap());
+        assert!(!clear(&path).unwrap());
+        assert_eq!(
+            None,
+            Cache::load(&path, 2023).unwrap().get(4, Part::Two, 1, 7)
+        );
+    }
+}
@@ -0,0 +1,128 @@
+use std::{
+    cell::RefCell,
+    fmt,
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant},
+};
+
+use crate::error::DayError;
+
+/// Set once by Ctrl-C, stops every running and pending part.
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    static CURRENT: RefCell<Token> = RefCell::new(Token::default());
+}
+
+/// Asks every running part to stop.
+pub fn interrupt() {
+    INTERRUPTED.store(true, Ordering::SeqCst);
+}
+
+pub fn interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reason {
+    TimedOut,
+    Interrupted,
+}
+
+impl Reason {
+    /// Short name used for record statuses.
+    pub fn status(&self) -> &'static str {
+        match self {
+            Reason::TimedOut => "timeout",
+            Reason::Interrupted => "interrupted",
+        }
+    }
+}
+
+impl fmt::Display for Reason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Reason::TimedOut => write!(f, "timed out"),
+            Reason::Interrupted => write!(f, "interrupted"),
+        }
+    }
+}
+
+/// Tells a running part whether it should give up.
+///
+/// Solutions cannot be stopped from the outside, so long loops are expected
+/// to poll [`check`] or a token from [`current`] every now and then.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Token {
+    deadline: Option<Instant>,
+}
+
+impl Token {
+    pub fn new(timeout: Option<Duration>) -> Self {
+        Self {
+            deadline: timeout.map(|timeout| Instant::now() + timeout),
+        }
+    }
+
+    pub fn reason(&self) -> Option<Reason> {
+        if interrupted() {
+            Some(Reason::Interrupted)
+        } else if self
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            Some(Reason::TimedOut)
+        } else {
+            None
+        }
+    }
+
+    pub fn check(&self) -> Result<(), DayError> {
+        match self.reason() {
+            Some(reason) => Err(DayError::Cancelled(reason)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The token of the part running on this thread. Threads spawned by the part,
+/// rayon workers included, need a copy of it.
+pub fn current() -> Token {
+    CURRENT.with(|token| *token.borrow())
+}
+
+/// Checks the token of the part running on this thread.
+pub fn check() -> Result<(), DayError> {
+    current().check()
+}
+
+/// Runs `f` with `token` as the current token of this thread.
+pub fn scoped<T>(token: Token, f: impl FnOnce() -> T) -> T {
+    let previous = CURRENT.with(|current| current.replace(token));
+    let res = f();
+    CURRENT.with(|current| current.replace(previous));
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token() {
+        assert_eq!(None, Token::new(None).reason());
+        assert_eq!(None, Token::new(Some(Duration::from_secs(60))).reason());
+        assert_eq!(
+            Some(Reason::TimedOut),
+            Token::new(Some(Duration::ZERO)).reason()
+        );
+    }
+
+    #[test]
+    fn test_scoped() {
+        assert!(check().is_ok());
+        let res = scoped(Token::new(Some(Duration::ZERO)), check);
+        assert!(matches!(res, Err(DayError::Cancelled(Reason::TimedOut))));
+        assert!(check().is_ok());
+    }
+}
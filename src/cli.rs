@@ -0,0 +1,590 @@
+use std::{fmt, path::PathBuf, str::FromStr, time::Duration};
+
+use clap::{Args, Parser, Subcommand};
+
+use adventofcode23::{
+    answers::DEFAULT_ANSWERS_FILE, cache::DEFAULT_CACHE_FILE, config::Config, report::Format,
+    solution::Part, submit::DEFAULT_HISTORY_FILE,
+};
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "aoc",
+    bin_name = "aoc",
+    version,
+    about = "Advent of Code solutions"
+)]
+pub struct Cli {
+    /// Event year. Defaults to `$AOC_YEAR` or the latest implemented year
+    #[arg(short, long, global = true)]
+    pub year: Option<u32>,
+
+    /// Log more: `-v` for info, `-vv` for debug, `-vvv` for trace
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    pub verbose: u8,
+
+    /// Log errors only
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    pub quiet: bool,
+
+    /// Also write the log as JSON lines to this file
+    #[arg(long, global = true, value_name = "PATH")]
+    pub log_file: Option<PathBuf>,
+
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Run one or more days
+    Run(RunArgs),
+    /// Measure parse and solve times over repeated runs
+    Bench(BenchArgs),
+    /// Check answers against the accepted ones in the answers file
+    Verify(VerifyArgs),
+    /// Re-run a day whenever its input or example files change
+    Watch(WatchArgs),
+    /// Parse the input of a day once and explore it interactively
+    Repl(ReplArgs),
+    /// Show every day in a dashboard to run parts from
+    Tui(TuiArgs),
+    /// Answer solve requests over a local HTTP API
+    Serve(ServeArgs),
+    /// Download puzzle inputs into the input directory
+    Fetch(FetchArgs),
+    /// Submit the answer of one part
+    Submit(SubmitArgs),
+    /// Add the module, example and input file of a new day
+    NewDay(NewDayArgs),
+    /// Manage the cache of computed answers
+    #[command(subcommand)]
+    Cache(CacheCommand),
+    /// Inspect the config files
+    #[command(subcommand)]
+    Config(ConfigCommand),
+}
+
+#[derive(Debug, Args)]
+#[command(group = clap::ArgGroup::new("selection").required(true))]
+pub struct RunArgs {
+    /// Days to run, e.g. `3`, `1-4` or `1,3,5`
+    #[arg(short, long, group = "selection")]
+    pub day: Option<DaySelection>,
+
+    /// Run every registered day
+    #[arg(short, long, group = "selection")]
+    pub all: bool,
+
+    /// Run only this part, both parts run when omitted
+    #[arg(short, long)]
+    pub part: Option<Part>,
+
+    /// Input file, `-` for stdin or a directory holding `YEAR/dayN` or
+    /// `dayN` files. Defaults to `$AOC_INPUT_DIR`, the configured
+    /// `input-dir` or `inputs/`
+    #[arg(short, long, value_name = "PATH")]
+    pub input: Option<PathBuf>,
+
+    /// Output format for the results, `text` unless configured
+    #[arg(short, long, value_enum)]
+    pub format: Option<Format>,
+
+    /// Number of days, or inputs with `--inputs-dir`, to run at the same
+    /// time, `0` for one per CPU. `1` unless configured
+    #[arg(short, long, value_name = "N")]
+    pub jobs: Option<usize>,
+
+    /// Run the puzzle examples and compare with their expected answers
+    #[arg(short, long, conflicts_with_all = ["input", "format"])]
+    pub example: bool,
+
+    /// Run against every input in this directory, comparing with the
+    /// `name.answer` files next to them
+    #[arg(long, value_name = "DIR", conflicts_with_all = ["input", "format", "example"])]
+    pub inputs_dir: Option<PathBuf>,
+
+    /// Seconds a part may run before it is stopped and reported as timed out
+    #[arg(long, value_name = "SECONDS", value_parser = parse_seconds)]
+    pub timeout: Option<Duration>,
+
+    /// Count allocations, bytes allocated and peak live bytes of each parse
+    /// and part. Days run one at a time so the counts do not mix
+    #[arg(long, conflicts_with = "jobs")]
+    pub mem: bool,
+
+    /// Solve every part even when its answer is cached
+    #[arg(long)]
+    pub no_cache: bool,
+
+    /// Answers computed earlier, keyed by input hash and solution version
+    #[arg(long, value_name = "PATH", default_value = DEFAULT_CACHE_FILE)]
+    pub cache_file: PathBuf,
+}
+
+impl RunArgs {
+    pub fn parts(&self) -> Vec<Part> {
+        selected_parts(self.part)
+    }
+
+    pub fn format(&self) -> Format {
+        self.format.unwrap_or_default()
+    }
+
+    pub fn jobs(&self) -> usize {
+        self.jobs.unwrap_or(1)
+    }
+
+    /// Fills the flags left out with the configured values. `--mem` keeps
+    /// running one day at a time.
+    pub fn with_config(self, config: &Config) -> Self {
+        Self {
+            format: self.format.or(config.format),
+            jobs: if self.mem {
+                self.jobs
+            } else {
+                self.jobs.or(config.jobs)
+            },
+            timeout: self.timeout.or(config.timeout()),
+            ..self
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct BenchArgs {
+    /// Days to benchmark, every registered day when omitted
+    #[arg(short, long)]
+    pub day: Option<DaySelection>,
+
+    /// Benchmark only this part, both parts run when omitted
+    #[arg(short, long)]
+    pub part: Option<Part>,
+
+    /// Untimed runs before measuring
+    #[arg(short, long, default_value_t = 3)]
+    pub warmup: u32,
+
+    /// Measured runs
+    #[arg(short = 'n', long, default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..))]
+    pub iterations: u32,
+
+    /// Input file, `-` for stdin or a directory holding `YEAR/dayN` or
+    /// `dayN` files. Defaults to `$AOC_INPUT_DIR`, the configured
+    /// `input-dir` or `inputs/`
+    #[arg(short, long, value_name = "PATH")]
+    pub input: Option<PathBuf>,
+}
+
+impl BenchArgs {
+    pub fn parts(&self) -> Vec<Part> {
+        selected_parts(self.part)
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct VerifyArgs {
+    /// Days to verify, every registered day when omitted
+    #[arg(short, long)]
+    pub day: Option<DaySelection>,
+
+    /// Verify only this part, both parts run when omitted
+    #[arg(short, long)]
+    pub part: Option<Part>,
+
+    /// Input file, `-` for stdin or a directory holding `YEAR/dayN` or
+    /// `dayN` files. Defaults to `$AOC_INPUT_DIR`, the configured
+    /// `input-dir` or `inputs/`
+    #[arg(short, long, value_name = "PATH")]
+    pub input: Option<PathBuf>,
+
+    /// File with the accepted answers
+    #[arg(short, long, value_name = "PATH", default_value = DEFAULT_ANSWERS_FILE)]
+    pub answers: PathBuf,
+
+    /// Store the current answers as the new accepted ones
+    #[arg(long)]
+    pub record: bool,
+}
+
+impl VerifyArgs {
+    pub fn parts(&self) -> Vec<Part> {
+        selected_parts(self.part)
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct WatchArgs {
+    /// Day to watch
+    #[arg(short, long, value_parser = clap::value_parser!(u32).range(1..=25))]
+    pub day: u32,
+
+    /// Watch only this part, both parts run when omitted
+    #[arg(short, long)]
+    pub part: Option<Part>,
+
+    /// Input file or a directory holding `YEAR/dayN` or `dayN` files.
+    /// Defaults to `$AOC_INPUT_DIR`, the configured `input-dir` or `inputs/`
+    #[arg(short, long, value_name = "PATH")]
+    pub input: Option<PathBuf>,
+
+    /// Milliseconds between checks for changes
+    #[arg(long, default_value_t = 500, value_name = "MS")]
+    pub interval: u64,
+}
+
+impl WatchArgs {
+    pub fn parts(&self) -> Vec<Part> {
+        selected_parts(self.part)
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct ReplArgs {
+    /// Day to explore
+    #[arg(short, long, value_parser = clap::value_parser!(u32).range(1..=25))]
+    pub day: u32,
+
+    /// Input file or a directory holding `YEAR/dayN` or `dayN` files.
+    /// Defaults to `$AOC_INPUT_DIR`, the configured `input-dir` or `inputs/`
+    #[arg(short, long, value_name = "PATH")]
+    pub input: Option<PathBuf>,
+
+    /// Explore the puzzle example instead of the input
+    #[arg(short, long, conflicts_with = "input")]
+    pub example: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct TuiArgs {
+    /// Directory holding `YEAR/dayN` or `dayN` files.
+    /// Defaults to `$AOC_INPUT_DIR`, the configured `input-dir` or `inputs/`
+    #[arg(short, long, value_name = "DIR")]
+    pub input: Option<PathBuf>,
+
+    /// File with the accepted answers, which earn the stars
+    #[arg(short, long, value_name = "PATH", default_value = DEFAULT_ANSWERS_FILE)]
+    pub answers: PathBuf,
+}
+
+#[derive(Debug, Args)]
+pub struct ServeArgs {
+    /// Port to listen on, on 127.0.0.1
+    #[arg(short, long, default_value_t = 8023)]
+    pub port: u16,
+
+    /// Seconds a part may run before it is stopped and reported as timed out
+    #[arg(long, value_name = "SECONDS", value_parser = parse_seconds)]
+    pub timeout: Option<Duration>,
+}
+
+impl ServeArgs {
+    pub fn with_config(self, config: &Config) -> Self {
+        Self {
+            timeout: self.timeout.or(config.timeout()),
+            ..self
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct FetchArgs {
+    /// Days to download, e.g. `3`, `1-4` or `1,3,5`
+    #[arg(short, long)]
+    pub day: DaySelection,
+
+    /// Directory to store the inputs in.
+    /// Defaults to `YEAR/` in `$AOC_INPUT_DIR`, the configured
+    /// `input-dir` or `inputs/`
+    #[arg(long, value_name = "DIR")]
+    pub dir: Option<PathBuf>,
+
+    /// Server to download from. Defaults to `$AOC_BASE_URL` or the real site
+    #[arg(long, value_name = "URL")]
+    pub base_url: Option<String>,
+
+    /// File holding the session token, used when `$AOC_SESSION` is unset.
+    /// Defaults to the configured `session-file` or `~/.config/aoc/session`
+    #[arg(long, value_name = "PATH")]
+    pub session_file: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct SubmitArgs {
+    /// Day to submit
+    #[arg(short, long, value_parser = clap::value_parser!(u32).range(1..=25))]
+    pub day: u32,
+
+    /// Part to submit
+    #[arg(short, long)]
+    pub part: Part,
+
+    /// Input file or a directory holding `YEAR/dayN` or `dayN` files.
+    /// Defaults to `$AOC_INPUT_DIR`, the configured `input-dir` or `inputs/`
+    #[arg(short, long, value_name = "PATH")]
+    pub input: Option<PathBuf>,
+
+    /// Server to submit to. Defaults to `$AOC_BASE_URL` or the real site
+    #[arg(long, value_name = "URL")]
+    pub base_url: Option<String>,
+
+    /// File holding the session token, used when `$AOC_SESSION` is unset.
+    /// Defaults to the configured `session-file` or `~/.config/aoc/session`
+    #[arg(long, value_name = "PATH")]
+    pub session_file: Option<PathBuf>,
+
+    /// Earlier submissions and their outcome
+    #[arg(long, value_name = "PATH", default_value = DEFAULT_HISTORY_FILE)]
+    pub history: PathBuf,
+
+    /// File the answer is recorded in once it is correct
+    #[arg(short, long, value_name = "PATH", default_value = DEFAULT_ANSWERS_FILE)]
+    pub answers: PathBuf,
+}
+
+#[derive(Debug, Args)]
+pub struct NewDayArgs {
+    /// Day to add
+    #[arg(value_parser = clap::value_parser!(u32).range(1..=25))]
+    pub day: u32,
+
+    /// Puzzle title, `Day N` when omitted
+    #[arg(short, long)]
+    pub name: Option<String>,
+
+    /// Crate root holding the `src/adventYY` modules
+    #[arg(long, value_name = "DIR", default_value = ".")]
+    pub root: PathBuf,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum CacheCommand {
+    /// Remove every cached answer
+    Clear {
+        /// Cache to remove
+        #[arg(long, value_name = "PATH", default_value = DEFAULT_CACHE_FILE)]
+        cache_file: PathBuf,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ConfigCommand {
+    /// Print the configuration in effect, after the user and project files
+    /// and the environment
+    Show,
+}
+
+fn selected_parts(part: Option<Part>) -> Vec<Part> {
+    match part {
+        Some(part) => vec![part],
+        None => Part::ALL.to_vec(),
+    }
+}
+
+fn parse_seconds(seconds: &str) -> Result<Duration, String> {
+    seconds
+        .parse::<f64>()
+        .ok()
+        .filter(|&seconds| seconds > 0.0)
+        .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+        .ok_or_else(|| format!("invalid number of seconds `{}`", seconds))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DaySelection {
+    days: Vec<u32>,
+}
+
+impl DaySelection {
+    pub fn days(&self) -> &[u32] {
+        &self.days
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct DaySelectionError(String);
+
+impl fmt::Display for DaySelectionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for DaySelectionError {}
+
+fn parse_day(day: &str) -> Result<u32, DaySelectionError> {
+    match day.trim().parse::<u32>() {
+        Ok(day @ 1..=25) => Ok(day),
+        Ok(day) => Err(DaySelectionError(format!(
+            "day {} is out of range, expected 1-25",
+            day
+        ))),
+        Err(_) => Err(DaySelectionError(format!("invalid day `{}`", day.trim()))),
+    }
+}
+
+impl FromStr for DaySelection {
+    type Err = DaySelectionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut days = Vec::new();
+        for part in s.split(',') {
+            match part.split_once('-') {
+                Some((start, end)) => {
+                    let start = parse_day(start)?;
+                    let end = parse_day(end)?;
+                    if start > end {
+                        return Err(DaySelectionError(format!(
+                            "invalid range `{}`, start is after end",
+                            part
+                        )));
+                    }
+                    days.extend(start..=end);
+                }
+                None => days.push(parse_day(part)?),
+            }
+        }
+
+        days.sort_unstable();
+        days.dedup();
+        Ok(Self { days })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory;
+
+    use super::*;
+
+    #[test]
+    fn test_day_selection_single() {
+        let res: DaySelection = "3".parse().unwrap();
+        assert_eq!(vec![3], res.days());
+    }
+
+    #[test]
+    fn test_day_selection_range_and_list() {
+        let res: DaySelection = "1-4".parse().unwrap();
+        assert_eq!(vec![1, 2, 3, 4], res.days());
+
+        let res: DaySelection = "5,1-2,2".parse().unwrap();
+        assert_eq!(vec![1, 2, 5], res.days());
+    }
+
+    #[test]
+    fn test_day_selection_invalid() {
+        assert!("0".parse::<DaySelection>().is_err());
+        assert!("26".parse::<DaySelection>().is_err());
+        assert!("4-1".parse::<DaySelection>().is_err());
+        assert!("x".parse::<DaySelection>().is_err());
+        assert!("1-".parse::<DaySelection>().is_err());
+    }
+
+    #[test]
+    fn test_cli_requires_selection() {
+        assert!(Cli::try_parse_from(["adventofcode23", "run"]).is_err());
+        assert!(Cli::try_parse_from(["adventofcode23", "run", "--all", "--day", "1"]).is_err());
+        assert!(
+            Cli::try_parse_from(["adventofcode23", "run", "--day", "1", "--part", "3"]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_cli_name() {
+        let err = Cli::try_parse_from(["adventofcode23", "run"]).unwrap_err();
+        assert!(err.to_string().contains("Usage: aoc run"));
+        assert_eq!("aoc", Cli::command().get_name());
+    }
+
+    #[test]
+    fn test_cli_run() {
+        let cli =
+            Cli::try_parse_from(["adventofcode23", "run", "--day", "3", "--part", "2"]).unwrap();
+        let Command::Run(args) = cli.command else {
+            panic!("expected the run command");
+        };
+        assert_eq!(Some(Part::Two), args.part);
+        assert_eq!(vec![Part::Two], args.parts());
+        assert_eq!(vec![3], args.day.unwrap().days());
+        assert_eq!(None, args.timeout);
+        assert!(!args.no_cache);
+    }
+
+    #[test]
+    fn test_cli_verbosity() {
+        let cli = Cli::try_parse_from(["adventofcode23", "run", "-d", "1", "-vv"]).unwrap();
+        assert_eq!((2, false), (cli.verbose, cli.quiet));
+
+        let cli = Cli::try_parse_from(["adventofcode23", "-q", "run", "-d", "1"]).unwrap();
+        assert_eq!((0, true), (cli.verbose, cli.quiet));
+
+        assert!(Cli::try_parse_from(["adventofcode23", "-q", "-v", "run", "-d", "1"]).is_err());
+    }
+
+    #[test]
+    fn test_run_with_config() {
+        let config = Config::parse("format = \"json\"\njobs = 4\ntimeout = 2\n").unwrap();
+        let run = |args: &[&str]| {
+            let cli =
+                Cli::try_parse_from(["adventofcode23", "run", "--all"].iter().chain(args)).unwrap();
+            let Command::Run(args) = cli.command else {
+                panic!("expected the run command");
+            };
+            args.with_config(&config)
+        };
+
+        let args = run(&[]);
+        assert_eq!((Format::Json, 4), (args.format(), args.jobs()));
+        assert_eq!(Some(Duration::from_secs(2)), args.timeout);
+
+        let args = run(&["-f", "csv", "-j", "2", "--timeout", "1"]);
+        assert_eq!((Format::Csv, 2), (args.format(), args.jobs()));
+        assert_eq!(Some(Duration::from_secs(1)), args.timeout);
+
+        assert_eq!(1, run(&["--mem"]).jobs());
+    }
+
+    #[test]
+    fn test_cli_cache_clear() {
+        let cli = Cli::try_parse_from(["adventofcode23", "cache", "clear"]).unwrap();
+        let Command::Cache(CacheCommand::Clear { cache_file }) = cli.command else {
+            panic!("expected the cache clear command");
+        };
+        assert_eq!(PathBuf::from(DEFAULT_CACHE_FILE), cache_file);
+    }
+
+    #[test]
+    fn test_cli_run_timeout() {
+        let cli =
+            Cli::try_parse_from(["adventofcode23", "run", "-d", "5", "--timeout", "2.5"]).unwrap();
+        let Command::Run(args) = cli.command else {
+            panic!("expected the run command");
+        };
+        assert_eq!(Some(Duration::from_millis(2500)), args.timeout);
+
+        for timeout in ["0", "-1", "soon"] {
+            assert!(Cli::try_parse_from([
+                "adventofcode23",
+                "run",
+                "-d",
+                "5",
+                "--timeout",
+                timeout
+            ])
+            .is_err());
+        }
+    }
+
+    #[test]
+    fn test_cli_bench_defaults() {
+        let cli = Cli::try_parse_from(["adventofcode23", "bench"]).unwrap();
+        let Command::Bench(args) = cli.command else {
+            panic!("expected the bench command");
+        };
+        assert_eq!(None, args.day);
+        assert_eq!(3, args.warmup);
+        assert_eq!(10, args.iterations);
+        assert!(Cli::try_parse_from(["adventofcode23", "bench", "-n", "0"]).is_err());
+    }
+}
@@ -0,0 +1,247 @@
+use std::{
+    env, fmt, fs, io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::report::Format;
+
+/// Project config, looked up in the working directory.
+pub const PROJECT_CONFIG_FILE: &str = "aoc.toml";
+
+/// `$XDG_CONFIG_HOME/aoc` or `~/.config/aoc`.
+pub fn config_dir() -> Option<PathBuf> {
+    let config = match env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(env::var_os("HOME")?).join(".config"),
+    };
+    Some(config.join("aoc"))
+}
+
+/// User config, `config.toml` in [`config_dir`].
+pub fn user_file() -> Option<PathBuf> {
+    Some(config_dir()?.join("config.toml"))
+}
+
+/// Defaults for the command line flags, every key is optional:
+///
+/// ```toml
+/// input-dir = "../aoc-inputs"
+/// format = "json"
+/// jobs = 4
+/// timeout = 10.0
+/// session-file = "/run/secrets/aoc-session"
+/// skip = [5]
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Config {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input_dir: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<Format>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jobs: Option<usize>,
+    /// Seconds a part may run.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_file: Option<PathBuf>,
+    /// Days left out when no days are selected, e.g. by `run --all`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skip: Option<Vec<u32>>,
+}
+
+impl Config {
+    pub fn parse(content: &str) -> io::Result<Self> {
+        let config: Self = toml::from_str(content)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        if let Some(timeout) = config.timeout {
+            if !(timeout > 0.0 && Duration::try_from_secs_f64(timeout).is_ok()) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("invalid timeout `{}`, expected positive seconds", timeout),
+                ));
+            }
+        }
+        Ok(config)
+    }
+
+    /// Reads `path`, `None` when there is no such file. Relative paths in it
+    /// are resolved against the directory of `path`.
+    pub fn load(path: &Path) -> io::Result<Option<Self>> {
+        match fs::read_to_string(path) {
+            Ok(content) => {
+                let dir = path.parent().unwrap_or(Path::new(""));
+                let config = Self::parse(&content)?;
+                Ok(Some(Self {
+                    input_dir: config.input_dir.map(|p| dir.join(p)),
+                    session_file: config.session_file.map(|p| dir.join(p)),
+                    ..config
+                }))
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// The keys set in `other` replace the ones of `self`.
+    pub fn merge(self, other: Config) -> Config {
+        Config {
+            input_dir: other.input_dir.or(self.input_dir),
+            format: other.format.or(self.format),
+            jobs: other.jobs.or(self.jobs),
+            timeout: other.timeout.or(self.timeout),
+            session_file: other.session_file.or(self.session_file),
+            skip: other.skip.or(self.skip),
+        }
+    }
+
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+            .and_then(|timeout| Duration::try_from_secs_f64(timeout).ok())
+    }
+
+    pub fn skip(&self) -> &[u32] {
+        self.skip.as_deref().unwrap_or_default()
+    }
+}
+
+/// The merged config and the files it was read from, in load order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Loaded {
+    pub config: Config,
+    pub files: Vec<PathBuf>,
+}
+
+impl Loaded {
+    /// Reads the user config, then the project one on top of it.
+    pub fn load() -> Result<Self, ConfigError> {
+        let paths = user_file()
+            .into_iter()
+            .chain([PathBuf::from(PROJECT_CONFIG_FILE)]);
+        Self::load_from(paths)
+    }
+
+    pub fn load_from(paths: impl IntoIterator<Item = PathBuf>) -> Result<Self, ConfigError> {
+        let mut loaded = Self::default();
+        for path in paths {
+            match Config::load(&path) {
+                Ok(Some(config)) => {
+                    loaded.config = loaded.config.merge(config);
+                    loaded.files.push(path);
+                }
+                Ok(None) => {}
+                Err(err) => return Err(ConfigError { path, err }),
+            }
+        }
+        Ok(loaded)
+    }
+}
+
+#[derive(Debug)]
+pub struct ConfigError {
+    pub path: PathBuf,
+    pub err: io::Error,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid config {}: {}", self.path.display(), self.err)
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// `config` as TOML, preceded by comments naming the `files` it came from.
+pub fn render(config: &Config, files: &[PathBuf]) -> String {
+    let mut out = String::new();
+    if files.is_empty() {
+        out.push_str("# no config files, defaults only\n");
+    }
+    for file in files {
+        out.push_str(&format!("# {}\n", file.display()));
+    }
+    out.push_str(&toml::to_string(config).expect("configs serialize"));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::process;
+
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let config = Config::parse(
+            "input-dir = \"in\"\nformat = \"json\"\njobs = 4\ntimeout = 10\nskip = [5]\n",
+        )
+        .unwrap();
+        assert_eq!(
+            Config {
+                input_dir: Some(PathBuf::from("in")),
+                format: Some(Format::Json),
+                jobs: Some(4),
+                timeout: Some(10.0),
+                session_file: None,
+                skip: Some(vec![5]),
+            },
+            config
+        );
+        assert_eq!(Some(Duration::from_secs(10)), config.timeout());
+        assert_eq!(Config::default(), Config::parse("").unwrap());
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(Config::parse("jobz = 4\n").is_err());
+        assert!(Config::parse("format = \"xml\"\n").is_err());
+        assert!(Config::parse("timeout = 0\n").is_err());
+        assert!(Config::parse("timeout = 1e20\n").is_err());
+        assert!(Config::parse("timeout = nan\n").is_err());
+    }
+
+    #[test]
+    fn test_merge() {
+        let user = Config::parse("jobs = 4\nformat = \"csv\"\nskip = [5]\n").unwrap();
+        let project = Config::parse("jobs = 2\nskip = []\n").unwrap();
+
+        let res = user.merge(project);
+        assert_eq!(Some(2), res.jobs);
+        assert_eq!(Some(Format::Csv), res.format);
+        assert_eq!(&[] as &[u32], res.skip());
+    }
+
+    #[test]
+    fn test_load_from() {
+        let dir = env::temp_dir().join(format!("aoc-config-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let (user, project) = (dir.join("user.toml"), dir.join("aoc.toml"));
+        fs::write(&user, "jobs = 4\ntimeout = 2.5\ninput-dir = \"inputs\"\n").unwrap();
+        fs::write(&project, "jobs = 2\n").unwrap();
+
+        let loaded =
+            Loaded::load_from([user.clone(), dir.join("missing.toml"), project.clone()]).unwrap();
+        assert_eq!(vec![user.clone(), project.clone()], loaded.files);
+        assert_eq!(Some(2), loaded.config.jobs);
+        assert_eq!(Some(2.5), loaded.config.timeout);
+        assert_eq!(Some(dir.join("inputs")), loaded.config.input_dir);
+
+        fs::write(&project, "jobs = \"many\"\n").unwrap();
+        let err = Loaded::load_from([user, project.clone()]).unwrap_err();
+        assert_eq!(project, err.path);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_render() {
+        let config = Config::parse("jobs = 2\nskip = [5]\n").unwrap();
+        assert_eq!(
+            "# aoc.toml\njobs = 2\nskip = [5]\n",
+            render(&config, &[PathBuf::from("aoc.toml")])
+        );
+    }
+}
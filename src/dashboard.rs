@@ -0,0 +1,311 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    answers::Answers,
+    bench::format_duration,
+    registry::Year,
+    runner::Record,
+    solution::{DynSolution, Part},
+    verify::{self, Verdict},
+};
+
+pub const DAYS: u32 = 25;
+
+const HELP: &str = "up/down select  enter run  q quit";
+const BAR_WIDTH: usize = 20;
+
+/// The last run of a part.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Run {
+    pub answer: String,
+    pub status: String,
+    pub time: Duration,
+}
+
+/// One part of one day.
+pub struct Row {
+    pub day: u32,
+    pub part: Part,
+    pub solution: Option<&'static dyn DynSolution>,
+    /// The answer in the answers file, which earned a star.
+    pub accepted: Option<String>,
+    pub last: Option<Run>,
+    running: Option<Instant>,
+}
+
+/// State of the full screen view of every day of a year.
+pub struct Dashboard {
+    year: u32,
+    rows: Vec<Row>,
+    selected: usize,
+    message: String,
+    progress: Option<(u64, u64)>,
+}
+
+impl Dashboard {
+    pub fn new(year: &Year, answers: &Answers) -> Self {
+        let rows = (1..=DAYS)
+            .flat_map(|day| Part::ALL.map(|part| (day, part)))
+            .map(|(day, part)| Row {
+                day,
+                part,
+                solution: year.get(day),
+                accepted: answers.get(year.year, day, part).map(String::from),
+                last: None,
+                running: None,
+            })
+            .collect();
+
+        Self {
+            year: year.year,
+            rows,
+            selected: 0,
+            message: String::new(),
+            progress: None,
+        }
+    }
+
+    pub fn selected(&self) -> &Row {
+        &self.rows[self.selected]
+    }
+
+    pub fn select_next(&mut self) {
+        self.selected = (self.selected + 1).min(self.rows.len() - 1);
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.rows.iter().any(|row| row.running.is_some())
+    }
+
+    /// Marks the selected part as running and returns what to run, or says
+    /// why it cannot run.
+    pub fn start(&mut self) -> Option<(&'static dyn DynSolution, Part)> {
+        if self.is_running() {
+            self.message = "wait for the running part to finish".to_string();
+            return None;
+        }
+        let row = &mut self.rows[self.selected];
+        let Some(solution) = row.solution else {
+            self.message = format!("no solution for day {}", row.day);
+            return None;
+        };
+
+        row.running = Some(Instant::now());
+        self.progress = None;
+        self.message = format!("running day {} part {}", row.day, row.part);
+        Some((solution, row.part))
+    }
+
+    pub fn set_progress(&mut self, progress: Option<(u64, u64)>) {
+        self.progress = progress;
+    }
+
+    /// Stores the result of the running part.
+    pub fn finish(&mut self, record: &Record) {
+        let Some(row) = self
+            .rows
+            .iter_mut()
+            .find(|row| row.day == record.day && row.part == record.part)
+        else {
+            return;
+        };
+
+        let verdict = verify::compare(row.accepted.as_deref(), record);
+        let status = match verdict {
+            Verdict::Error => record.status().to_uppercase(),
+            verdict => verdict.to_string(),
+        };
+        let answer = match (record.answer(), record.error()) {
+            (Some(answer), _) => answer.to_string(),
+            (None, Some(err)) => err.to_string(),
+            (None, None) => String::new(),
+        };
+        row.running = None;
+        row.last = Some(Run {
+            answer,
+            status,
+            time: record.solve_time,
+        });
+        self.progress = None;
+        self.message = format!("day {} part {} done", record.day, record.part);
+    }
+
+    /// The screen as lines of at most `width` characters, and the index of
+    /// the line of the selected part.
+    pub fn view(&self, width: usize, height: usize) -> (Vec<String>, usize) {
+        let stars = self
+            .rows
+            .iter()
+            .filter(|row| row.accepted.is_some())
+            .count();
+        let mut lines = vec![
+            format!(
+                "Advent of Code {}  {}/{} stars",
+                self.year,
+                stars,
+                self.rows.len()
+            ),
+            format!(
+                "{:>3}  {:>4}  {:<32}  {:1}  {:<20}  {:>9}  {}",
+                "day", "part", "name", "*", "answer", "time", "status"
+            ),
+        ];
+
+        // Two header lines and the footer.
+        let visible = height.saturating_sub(3).max(1);
+        let offset = (self.selected + 1).saturating_sub(visible);
+        lines.extend(
+            self.rows
+                .iter()
+                .skip(offset)
+                .take(visible)
+                .map(|row| self.line(row)),
+        );
+        lines.push(if self.message.is_empty() {
+            HELP.to_string()
+        } else {
+            format!("{}  |  {}", self.message, HELP)
+        });
+
+        let lines = lines
+            .into_iter()
+            .map(|line| line.chars().take(width).collect())
+            .collect();
+        (lines, 2 + self.selected - offset)
+    }
+
+    fn line(&self, row: &Row) -> String {
+        let name = row.solution.map_or("-", |solution| solution.name());
+        let star = if row.accepted.is_some() { "*" } else { "" };
+        let (answer, time, status) = match (row.running, &row.last) {
+            (Some(start), _) => (
+                self.progress.map_or("...".to_string(), bar),
+                format_duration(start.elapsed()),
+                "RUNNING".to_string(),
+            ),
+            (None, Some(run)) => (
+                run.answer.clone(),
+                format_duration(run.time),
+                run.status.clone(),
+            ),
+            (None, None) => Default::default(),
+        };
+
+        let name: String = name.chars().take(32).collect();
+        let line = format!(
+            "{:>3}  {:>4}  {:<32}  {:1}  {:<20}  {:>9}  {}",
+            row.day,
+            row.part.number(),
+            name,
+            star,
+            answer,
+            time,
+            status
+        );
+        line.trim_end().to_string()
+    }
+}
+
+/// `[#####     ]  50%` for `position` out of `length`.
+fn bar((position, length): (u64, u64)) -> String {
+    let ratio = if length == 0 {
+        0.0
+    } else {
+        (position as f64 / length as f64).min(1.0)
+    };
+    let done = (ratio * BAR_WIDTH as f64) as usize;
+    format!(
+        "[{}{}] {:>3.0}%",
+        "#".repeat(done),
+        " ".repeat(BAR_WIDTH - done),
+        ratio * 100.0
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{error::DayError, registry};
+
+    fn dashboard() -> Dashboard {
+        let answers = Answers::parse("[2023.day1]\npart1 = \"142\"\n").unwrap();
+        Dashboard::new(registry::get(2023).unwrap(), &answers)
+    }
+
+    #[test]
+    fn test_view() {
+        let (lines, selected) = dashboard().view(200, 10);
+        assert_eq!("Advent of Code 2023  1/50 stars", lines[0]);
+        assert_eq!(10, lines.len());
+        assert_eq!(2, selected);
+        assert!(lines[2].starts_with("  1     1  Trebuchet?!"));
+        assert!(lines[2].ends_with("  *"));
+        assert_eq!(HELP, lines[9]);
+
+        let (lines, _) = dashboard().view(20, 10);
+        assert!(lines.iter().all(|line| line.chars().count() <= 20));
+    }
+
+    #[test]
+    fn test_scroll() {
+        let mut dashboard = dashboard();
+        for _ in 0..60 {
+            dashboard.select_next();
+        }
+        assert_eq!(25, dashboard.selected().day);
+        assert_eq!(Part::Two, dashboard.selected().part);
+
+        let (lines, selected) = dashboard.view(200, 10);
+        assert_eq!(8, selected);
+        assert!(lines[selected].starts_with(" 25     2  -"));
+    }
+
+    #[test]
+    fn test_run() {
+        let mut dashboard = dashboard();
+        let (solution, part) = dashboard.start().unwrap();
+        assert_eq!((1, Part::One), (solution.day(), part));
+        assert!(dashboard.start().is_none());
+
+        dashboard.set_progress(Some((5, 10)));
+        let (lines, _) = dashboard.view(200, 10);
+        assert!(lines[2].contains("[##########          ]  50%"));
+        assert!(lines[2].ends_with("RUNNING"));
+
+        dashboard.finish(&Record {
+            day: 1,
+            ..Record::solved(Part::One, 142)
+        });
+        assert!(!dashboard.is_running());
+        let run = dashboard.selected().last.clone().unwrap();
+        assert_eq!(("142", "PASS"), (run.answer.as_str(), run.status.as_str()));
+
+        dashboard.select_next();
+        dashboard.start();
+        let err = DayError::Invalid("oops".to_string());
+        dashboard.finish(&Record {
+            day: 1,
+            ..Record::failed(Part::Two, err)
+        });
+        let run = dashboard.selected().last.clone().unwrap();
+        assert_eq!(
+            ("invalid input: oops", "ERROR"),
+            (run.answer.as_str(), run.status.as_str())
+        );
+    }
+
+    #[test]
+    fn test_start_without_solution() {
+        let mut dashboard = dashboard();
+        for _ in 0..48 {
+            dashboard.select_next();
+        }
+        assert!(dashboard.start().is_none());
+        let (lines, _) = dashboard.view(200, 10);
+        assert!(lines[9].starts_with("no solution for day 25"));
+    }
+}
@@ -0,0 +1,168 @@
+use std::fmt::Write;
+
+/// Position of a token inside the input it was sliced from.
+///
+/// Parsers only ever see sub-slices of the original input, so recording the
+/// address of the token is enough to find its line and column later on, once
+/// the whole input is available again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    addr: usize,
+    len: usize,
+}
+
+impl Span {
+    pub fn of(token: &str) -> Self {
+        Self {
+            addr: token.as_ptr() as usize,
+            len: token.len(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+    pub width: usize,
+    pub source_line: String,
+}
+
+impl Location {
+    /// Resolves `span` against `input`, `None` when the token was not sliced
+    /// out of `input`.
+    pub fn find(input: &str, span: Span) -> Option<Self> {
+        let base = input.as_ptr() as usize;
+        if span.addr < base || span.addr + span.len > base + input.len() {
+            return None;
+        }
+
+        let offset = span.addr - base;
+        let line_start = input[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = input[offset..]
+            .find('\n')
+            .map_or(input.len(), |i| offset + i);
+        let token = &input[offset..offset + span.len];
+
+        Some(Self {
+            line: input[..line_start].matches('\n').count() + 1,
+            column: input[line_start..offset].chars().count() + 1,
+            width: token.chars().count().max(1),
+            source_line: input[line_start..line_end]
+                .trim_end_matches('\r')
+                .to_string(),
+        })
+    }
+}
+
+/// Renders a compiler style report:
+///
+/// ```text
+/// error: unexpected `purple`
+///  --> inputs/day2:3:29
+///   |
+/// 3 | Game 3: 8 green, 6 blue, 20 purple
+///   |                             ^^^^^^
+///   = hint: expected `red`, `green` or `blue`
+/// ```
+pub fn render(
+    file: &str,
+    message: &str,
+    location: Option<&Location>,
+    hint: Option<&str>,
+) -> String {
+    let mut out = format!("error: {}\n", message);
+
+    let gutter = location.map_or(1, |l| l.line.to_string().len());
+    let pad = " ".repeat(gutter);
+    match location {
+        Some(location) => {
+            let _ = writeln!(
+                out,
+                "{}--> {}:{}:{}",
+                pad, file, location.line, location.column
+            );
+            let _ = writeln!(out, "{} |", pad);
+            let _ = writeln!(out, "{} | {}", location.line, location.source_line);
+            let _ = writeln!(
+                out,
+                "{} | {}{}",
+                pad,
+                " ".repeat(location.column - 1),
+                "^".repeat(location.width)
+            );
+        }
+        None => {
+            let _ = writeln!(out, "{}--> {}", pad, file);
+        }
+    }
+
+    if let Some(hint) = hint {
+        let _ = writeln!(out, "{} = hint: {}", pad, hint);
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_location_find() {
+        let input = "Game 1: 3 blue\nGame 2: 4 purple\n";
+        let token = &input[25..31];
+        assert_eq!("purple", token);
+
+        let res = Location::find(input, Span::of(token)).unwrap();
+        assert_eq!(
+            Location {
+                line: 2,
+                column: 11,
+                width: 6,
+                source_line: "Game 2: 4 purple".to_string(),
+            },
+            res
+        );
+    }
+
+    #[test]
+    fn test_location_find_foreign_token() {
+        let input = "Game 1: 3 blue";
+        let other = String::from("blue");
+        assert_eq!(None, Location::find(input, Span::of(&other)));
+    }
+
+    #[test]
+    fn test_render() {
+        let location = Location {
+            line: 3,
+            column: 12,
+            width: 6,
+            source_line: "Game 3: 20 purple".to_string(),
+        };
+        let res = render(
+            "inputs/day2",
+            "unexpected `purple`",
+            Some(&location),
+            Some("expected `red`, `green` or `blue`"),
+        );
+
+        assert_eq!(
+            "error: unexpected `purple`
+ --> inputs/day2:3:12
+  |
+3 | Game 3: 20 purple
+  |            ^^^^^^
+  = hint: expected `red`, `green` or `blue`
+",
+            res
+        );
+    }
+
+    #[test]
+    fn test_render_without_location() {
+        let res = render("inputs/day5", "missing section `seeds`", None, None);
+        assert_eq!("error: missing section `seeds`\n --> inputs/day5\n", res);
+    }
+}
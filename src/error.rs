@@ -0,0 +1,220 @@
+use std::{error::Error, fmt, io, num::ParseIntError, str::FromStr};
+
+use crate::{
+    cancel::Reason,
+    diagnostic::{self, Location, Span},
+};
+
+#[derive(Debug)]
+pub enum DayError {
+    Io(io::Error),
+    UnexpectedToken {
+        found: String,
+        expected: String,
+        span: Span,
+    },
+    MissingSection(String),
+    BadNumber {
+        token: String,
+        source: ParseIntError,
+        span: Span,
+    },
+    Invalid(String),
+    /// The part was stopped before it found an answer.
+    Cancelled(Reason),
+    /// A REPL query that cannot be answered, e.g. for a missing argument.
+    Query(String),
+    /// The solution panicked, with the panic message.
+    Panicked(String),
+    /// Any of the above, pinned to the place in the input it came from.
+    Located {
+        error: Box<DayError>,
+        location: Location,
+    },
+}
+
+impl DayError {
+    pub fn unexpected(found: &str, expected: impl Into<String>) -> Self {
+        DayError::UnexpectedToken {
+            found: found.to_string(),
+            expected: expected.into(),
+            span: Span::of(found),
+        }
+    }
+
+    pub fn missing(section: impl Into<String>) -> Self {
+        DayError::MissingSection(section.into())
+    }
+
+    /// Resolves the span of the offending token against the full `input`.
+    pub fn locate(self, input: &str) -> Self {
+        let span = match &self {
+            DayError::UnexpectedToken { span, .. } | DayError::BadNumber { span, .. } => *span,
+            _ => return self,
+        };
+
+        match Location::find(input, span) {
+            Some(location) => DayError::Located {
+                error: Box::new(self),
+                location,
+            },
+            None => self,
+        }
+    }
+
+    pub fn location(&self) -> Option<&Location> {
+        match self {
+            DayError::Located { location, .. } => Some(location),
+            _ => None,
+        }
+    }
+
+    fn message(&self) -> String {
+        match self {
+            DayError::UnexpectedToken { found, .. } => format!("unexpected `{}`", found),
+            DayError::BadNumber { token, .. } => format!("bad number `{}`", token),
+            DayError::Located { error, .. } => error.message(),
+            err => err.to_string(),
+        }
+    }
+
+    fn hint(&self) -> Option<String> {
+        match self {
+            DayError::UnexpectedToken { expected, .. } => Some(format!("expected {}", expected)),
+            DayError::MissingSection(section) => {
+                Some(format!("the input needs a `{}` section", section))
+            }
+            DayError::BadNumber { source, .. } => Some(format!("{}", source)),
+            DayError::Located { error, .. } => error.hint(),
+            _ => None,
+        }
+    }
+
+    /// Compiler style report of the error for input read from `file`.
+    pub fn render(&self, file: &str) -> String {
+        diagnostic::render(
+            file,
+            &self.message(),
+            self.location(),
+            self.hint().as_deref(),
+        )
+    }
+}
+
+impl fmt::Display for DayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DayError::Io(err) => write!(f, "failed to load input: {}", err),
+            DayError::UnexpectedToken {
+                found, expected, ..
+            } => {
+                write!(f, "unexpected `{}`, expected {}", found, expected)
+            }
+            DayError::MissingSection(section) => write!(f, "missing section `{}`", section),
+            DayError::BadNumber { token, source, .. } => {
+                write!(f, "bad number `{}`: {}", token, source)
+            }
+            DayError::Invalid(reason) => write!(f, "invalid input: {}", reason),
+            DayError::Cancelled(reason) => write!(f, "{}", reason),
+            DayError::Query(message) => write!(f, "{}", message),
+            DayError::Panicked(message) => write!(f, "the solution panicked: {}", message),
+            DayError::Located { error, location } => {
+                write!(f, "{}:{}: {}", location.line, location.column, error)
+            }
+        }
+    }
+}
+
+impl Error for DayError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            DayError::Io(err) => Some(err),
+            DayError::BadNumber { source, .. } => Some(source),
+            DayError::Located { error, .. } => error.source(),
+            _ => None,
+        }
+    }
+}
+
+impl From<Reason> for DayError {
+    fn from(reason: Reason) -> Self {
+        DayError::Cancelled(reason)
+    }
+}
+
+impl From<io::Error> for DayError {
+    fn from(value: io::Error) -> Self {
+        DayError::Io(value)
+    }
+}
+
+/// Parses a trimmed integer token, keeping the token around for the error.
+pub fn parse_number<T>(token: &str) -> Result<T, DayError>
+where
+    T: FromStr<Err = ParseIntError>,
+{
+    let token = token.trim();
+    token.parse::<T>().map_err(|source| DayError::BadNumber {
+        token: token.to_string(),
+        source,
+        span: Span::of(token),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_number() {
+        assert_eq!(12, parse_number::<i32>(" 12 ").unwrap());
+
+        let err = parse_number::<i32>("1x").unwrap_err();
+        assert_eq!(
+            "bad number `1x`: invalid digit found in string",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        let err = DayError::unexpected("purple", "`red`, `green` or `blue`");
+        assert_eq!(
+            "unexpected `purple`, expected `red`, `green` or `blue`",
+            err.to_string()
+        );
+
+        let err = DayError::missing("seeds");
+        assert_eq!("missing section `seeds`", err.to_string());
+    }
+
+    #[test]
+    fn test_locate_and_render() {
+        let input = "Game 1: 3 blue\nGame 2: 4 purple\n";
+        let err = DayError::unexpected(&input[25..31], "`red`, `green` or `blue`").locate(input);
+
+        assert_eq!(
+            "2:11: unexpected `purple`, expected `red`, `green` or `blue`",
+            err.to_string()
+        );
+        assert_eq!(
+            "error: unexpected `purple`
+ --> inputs/day2:2:11
+  |
+2 | Game 2: 4 purple
+  |           ^^^^^^
+  = hint: expected `red`, `green` or `blue`
+",
+            err.render("inputs/day2")
+        );
+    }
+
+    #[test]
+    fn test_locate_bad_number() {
+        let input = "Card  1x: 1 | 2";
+        let err = parse_number::<i32>(&input[5..8]).unwrap_err().locate(input);
+
+        assert_eq!(Some(7), err.location().map(|l| l.column));
+        assert_eq!(Some(2), err.location().map(|l| l.width));
+    }
+}
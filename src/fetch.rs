@@ -0,0 +1,307 @@
+use std::{
+    env, fmt, fs, io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use ureq::Agent;
+
+use crate::config;
+
+pub const SESSION_ENV: &str = "AOC_SESSION";
+pub const BASE_URL_ENV: &str = "AOC_BASE_URL";
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+#[derive(Debug)]
+pub enum FetchError {
+    MissingSession(Option<PathBuf>),
+    Status { url: String, status: u16 },
+    Transport { url: String, message: String },
+    Io(io::Error),
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FetchError::MissingSession(Some(path)) => write!(
+                f,
+                "no session token, set `{}` or write it to {}",
+                SESSION_ENV,
+                path.display()
+            ),
+            FetchError::MissingSession(None) => {
+                write!(f, "no session token, set `{}`", SESSION_ENV)
+            }
+            FetchError::Status { url, status } => {
+                write!(f, "GET {} returned {}", url, status)?;
+                match status {
+                    400 => write!(f, ", the session token is probably expired"),
+                    404 => write!(f, ", the puzzle is probably not unlocked yet"),
+                    _ => Ok(()),
+                }
+            }
+            FetchError::Transport { url, message } => write!(f, "GET {} failed: {}", url, message),
+            FetchError::Io(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for FetchError {}
+
+impl From<io::Error> for FetchError {
+    fn from(err: io::Error) -> Self {
+        FetchError::Io(err)
+    }
+}
+
+/// Where the session token is read from when `AOC_SESSION` is unset,
+/// `$XDG_CONFIG_HOME/aoc/session` or `~/.config/aoc/session`.
+pub fn session_file() -> Option<PathBuf> {
+    Some(config::config_dir()?.join("session"))
+}
+
+/// The session token from `AOC_SESSION`, or else from `file`.
+pub fn session(file: Option<&Path>) -> Result<String, FetchError> {
+    if let Ok(token) = env::var(SESSION_ENV) {
+        if !token.trim().is_empty() {
+            return Ok(token.trim().to_string());
+        }
+    }
+
+    let missing = || FetchError::MissingSession(file.map(Path::to_path_buf));
+    let file = file.ok_or_else(missing)?;
+    match fs::read_to_string(file) {
+        Ok(token) if !token.trim().is_empty() => Ok(token.trim().to_string()),
+        Ok(_) => Err(missing()),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Err(missing()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// `--base-url`, then `AOC_BASE_URL`, then the real site.
+pub fn base_url(flag: Option<&str>) -> String {
+    let url = match flag {
+        Some(url) => url.to_string(),
+        None => env::var(BASE_URL_ENV)
+            .ok()
+            .filter(|url| !url.is_empty())
+            .unwrap_or_else(|| DEFAULT_BASE_URL.to_string()),
+    };
+    url.trim_end_matches('/').to_string()
+}
+
+pub struct Client {
+    agent: Agent,
+    base_url: String,
+    session: String,
+}
+
+impl Client {
+    pub fn new(base_url: &str, session: &str) -> Self {
+        let agent = Agent::config_builder()
+            .http_status_as_error(false)
+            .timeout_global(Some(Duration::from_secs(30)))
+            .user_agent(USER_AGENT)
+            .build()
+            .into();
+        Self {
+            agent,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session: session.to_string(),
+        }
+    }
+
+    pub fn input(&self, year: u32, day: u32) -> Result<String, FetchError> {
+        let url = format!("{}/{}/day/{}/input", self.base_url, year, day);
+        let response = self
+            .agent
+            .get(&url)
+            .header("Cookie", &format!("session={}", self.session))
+            .call();
+        body(url, response)
+    }
+
+    /// Posts `answer` for `part` and returns the response page.
+    pub fn answer(
+        &self,
+        year: u32,
+        day: u32,
+        part: u8,
+        answer: &str,
+    ) -> Result<String, FetchError> {
+        let url = format!("{}/{}/day/{}/answer", self.base_url, year, day);
+        let response = self
+            .agent
+            .post(&url)
+            .header("Cookie", &format!("session={}", self.session))
+            .send_form([("level", part.to_string().as_str()), ("answer", answer)]);
+        body(url, response)
+    }
+}
+
+fn body(
+    url: String,
+    response: Result<ureq::http::Response<ureq::Body>, ureq::Error>,
+) -> Result<String, FetchError> {
+    let transport = |err: ureq::Error| FetchError::Transport {
+        url: url.clone(),
+        message: err.to_string(),
+    };
+
+    let mut response = response.map_err(transport)?;
+    let status = response.status().as_u16();
+    if status != 200 {
+        return Err(FetchError::Status { url, status });
+    }
+    response.body_mut().read_to_string().map_err(transport)
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Fetched {
+    Downloaded(PathBuf),
+    /// The input was already on disk, nothing was requested.
+    Existing(PathBuf),
+}
+
+/// The `dayN` or `dayN.gz` input of `day` in `dir`, if there is one.
+pub fn existing(dir: &Path, day: u32) -> Option<PathBuf> {
+    [format!("day{}", day), format!("day{}.gz", day)]
+        .into_iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.exists())
+}
+
+/// Downloads the input of `day` into `dir` unless a `dayN` or `dayN.gz` is
+/// already there.
+pub fn fetch(client: &Client, year: u32, day: u32, dir: &Path) -> Result<Fetched, FetchError> {
+    if let Some(path) = existing(dir, day) {
+        return Ok(Fetched::Existing(path));
+    }
+
+    let path = dir.join(format!("day{}", day));
+    let input = client.input(year, day)?;
+    fs::create_dir_all(dir)?;
+    // Written next to the target first, so an interrupted download never
+    // leaves a truncated input behind.
+    let partial = dir.join(format!(".day{}.partial", day));
+    fs::write(&partial, input)?;
+    fs::rename(&partial, &path)?;
+    Ok(Fetched::Downloaded(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{Read, Write},
+        net::TcpListener,
+        thread::{self, JoinHandle},
+    };
+
+    use super::*;
+    use crate::testing::temp_dir;
+
+    /// Answers a single request with `status` and `body` and returns the
+    /// request it received.
+    fn serve_once(status: u16, body: &'static str) -> (String, JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0; 1024];
+            let complete = |request: &[u8]| {
+                let request = String::from_utf8_lossy(request);
+                let Some((head, body)) = request.split_once("\r\n\r\n") else {
+                    return false;
+                };
+                let length = head
+                    .lines()
+                    .find_map(|line| line.strip_prefix("content-length: "))
+                    .map_or(0, |n| n.parse().unwrap());
+                body.len() >= length
+            };
+            while !complete(&request) {
+                let n = stream.read(&mut buf).unwrap();
+                if n == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buf[..n]);
+            }
+            write!(
+                stream,
+                "HTTP/1.1 {} X\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            )
+            .unwrap();
+            String::from_utf8(request).unwrap()
+        });
+        (url, handle)
+    }
+
+    #[test]
+    fn test_fetch_downloads_once() {
+        let dir = temp_dir("fetch-once");
+        let (url, server) = serve_once(200, "1abc2\n");
+        let client = Client::new(&url, "secret");
+
+        let res = fetch(&client, 2023, 1, &dir).unwrap();
+        assert_eq!(Fetched::Downloaded(dir.join("day1")), res);
+        assert_eq!("1abc2\n", fs::read_to_string(dir.join("day1")).unwrap());
+
+        let request = server.join().unwrap();
+        assert!(request.starts_with("GET /2023/day/1/input "));
+        assert!(request.contains("session=secret"));
+
+        // The server is gone, so a second request would fail.
+        let res = fetch(&client, 2023, 1, &dir).unwrap();
+        assert_eq!(Fetched::Existing(dir.join("day1")), res);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_fetch_error_status() {
+        let dir = temp_dir("fetch-status");
+        let (url, server) = serve_once(404, "not yet");
+        let client = Client::new(&url, "secret");
+
+        let res = fetch(&client, 2023, 25, &dir);
+        server.join().unwrap();
+        assert!(matches!(res, Err(FetchError::Status { status: 404, .. })));
+        assert!(!dir.join("day25").exists());
+    }
+
+    #[test]
+    fn test_answer() {
+        let (url, server) = serve_once(200, "<article>That's the right answer!</article>");
+        let client = Client::new(&url, "secret");
+
+        let res = client.answer(2023, 4, 2, "6857330").unwrap();
+        assert_eq!("<article>That's the right answer!</article>", res);
+
+        let request = server.join().unwrap();
+        assert!(request.starts_with("POST /2023/day/4/answer "));
+        assert!(request.ends_with("\r\n\r\nlevel=2&answer=6857330"));
+    }
+
+    #[test]
+    fn test_session_from_file() {
+        let dir = temp_dir("fetch-session");
+        let file = dir.join("session");
+        fs::write(&file, "  token\n").unwrap();
+
+        if env::var_os(SESSION_ENV).is_none() {
+            assert_eq!("token", session(Some(&file)).unwrap());
+            assert!(matches!(
+                session(Some(&dir.join("missing"))),
+                Err(FetchError::MissingSession(Some(_)))
+            ));
+        }
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,169 @@
+use std::{
+    env, fmt, fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use flate2::read::GzDecoder;
+
+pub const INPUT_DIR_ENV: &str = "AOC_INPUT_DIR";
+pub const DEFAULT_INPUT_DIR: &str = "inputs";
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+static CONFIGURED_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    Stdin,
+    File(PathBuf),
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Stdin => write!(f, "<stdin>"),
+            Source::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+/// Sets the `input-dir` of the config files, which only applies when
+/// `AOC_INPUT_DIR` is unset. Later calls are ignored.
+pub fn configure_input_dir(dir: PathBuf) {
+    let _ = CONFIGURED_DIR.set(dir);
+}
+
+/// Directory holding the `dayN` inputs, `AOC_INPUT_DIR`, the configured one
+/// or `inputs/`.
+pub fn input_dir() -> PathBuf {
+    match env::var_os(INPUT_DIR_ENV) {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => CONFIGURED_DIR
+            .get()
+            .cloned()
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_INPUT_DIR)),
+    }
+}
+
+/// Picks the input for `day` of `year`.
+///
+/// An explicit `input` wins: `-` reads stdin, a directory is searched like the
+/// input directory and anything else is used as is. Without one the input
+/// directory is searched.
+pub fn resolve(year: u32, day: u32, input: Option<&Path>) -> Source {
+    match input {
+        Some(path) if path == Path::new("-") => Source::Stdin,
+        Some(path) if path.is_dir() => Source::File(find_in(path, year, day)),
+        Some(path) => Source::File(path.to_path_buf()),
+        None => Source::File(find_in(&input_dir(), year, day)),
+    }
+}
+
+/// Where the inputs of `year` are stored, `<input dir>/<year>`.
+pub fn year_dir(year: u32) -> PathBuf {
+    input_dir().join(year.to_string())
+}
+
+/// Looks for `YEAR/dayN`, `YEAR/dayN.gz`, `dayN` and `dayN.gz` in `dir`, in
+/// that order. `YEAR/dayN` is returned when none exists.
+fn find_in(dir: &Path, year: u32, day: u32) -> PathBuf {
+    let year_dir = dir.join(year.to_string());
+    let candidates = [
+        year_dir.join(format!("day{}", day)),
+        year_dir.join(format!("day{}.gz", day)),
+        dir.join(format!("day{}", day)),
+        dir.join(format!("day{}.gz", day)),
+    ];
+    candidates
+        .iter()
+        .find(|path| path.exists())
+        .unwrap_or(&candidates[0])
+        .clone()
+}
+
+/// Reads the whole input, transparently inflating gzip data.
+pub fn load(source: &Source) -> io::Result<String> {
+    let mut bytes = Vec::new();
+    match source {
+        Source::Stdin => {
+            io::stdin().lock().read_to_end(&mut bytes)?;
+        }
+        Source::File(path) => {
+            bytes = fs::read(path)?;
+        }
+    }
+
+    if bytes.starts_with(&GZIP_MAGIC) {
+        let mut inflated = Vec::new();
+        GzDecoder::new(bytes.as_slice()).read_to_end(&mut inflated)?;
+        bytes = inflated;
+    }
+
+    String::from_utf8(bytes).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::{write::GzEncoder, Compression};
+
+    use super::*;
+    use crate::testing::temp_dir;
+
+    #[test]
+    fn test_resolve_explicit() {
+        assert_eq!(Source::Stdin, resolve(2023, 1, Some(Path::new("-"))));
+        assert_eq!(
+            Source::File(PathBuf::from("some/file")),
+            resolve(2023, 1, Some(Path::new("some/file")))
+        );
+    }
+
+    #[test]
+    fn test_resolve_directory() {
+        let dir = temp_dir("input-resolve");
+        let year = dir.join("2023");
+        let res = |day| resolve(2023, day, Some(&dir));
+        assert_eq!(Source::File(year.join("day3")), res(3));
+
+        fs::write(dir.join("day3.gz"), b"").unwrap();
+        assert_eq!(Source::File(dir.join("day3.gz")), res(3));
+
+        fs::write(dir.join("day3"), b"").unwrap();
+        assert_eq!(Source::File(dir.join("day3")), res(3));
+
+        fs::create_dir(&year).unwrap();
+        fs::write(year.join("day3.gz"), b"").unwrap();
+        assert_eq!(Source::File(year.join("day3.gz")), res(3));
+
+        fs::write(year.join("day3"), b"").unwrap();
+        assert_eq!(Source::File(year.join("day3")), res(3));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_plain_and_gzip() {
+        let dir = temp_dir("input-load");
+        fs::write(dir.join("day1"), "1abc2\n").unwrap();
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(b"pqr3stu8vwx\n").unwrap();
+        fs::write(dir.join("day2.gz"), encoder.finish().unwrap()).unwrap();
+
+        assert_eq!("1abc2\n", load(&Source::File(dir.join("day1"))).unwrap());
+        assert_eq!(
+            "pqr3stu8vwx\n",
+            load(&Source::File(dir.join("day2.gz"))).unwrap()
+        );
+        assert_eq!(
+            io::ErrorKind::NotFound,
+            load(&Source::File(dir.join("day9"))).unwrap_err().kind()
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,45 @@
+//! Advent of Code solutions and the tooling to run them.
+//!
+//! Every day lives in `adventYY::dayN` and exports its parsed model, a
+//! `parse` function and `part1`/`part2` functions, all returning
+//! [`Result`]s with a [`DayError`](error::DayError):
+//!
+//! ```
+//! use adventofcode23::advent23::day2;
+//!
+//! let games = day2::parse("Game 1: 3 blue, 4 red; 1 red, 2 green\n").unwrap();
+//! assert_eq!(1, day2::part1(&games).unwrap());
+//! assert_eq!(4 * 2 * 3, day2::part2(&games).unwrap());
+//! ```
+//!
+//! The same days are available type-erased through [`registry`] as
+//! [`DynSolution`](solution::DynSolution)s, which is what the `aoc` binary
+//! drives.
+
+pub mod advent23;
+pub mod answers;
+pub mod bench;
+pub mod cache;
+pub mod cancel;
+pub mod config;
+pub mod dashboard;
+pub mod diagnostic;
+pub mod error;
+pub mod fetch;
+pub mod input;
+pub mod logging;
+pub mod matrix;
+pub mod memory;
+pub mod progress;
+pub mod registry;
+pub mod repl;
+pub mod report;
+pub mod runner;
+pub mod scaffold;
+pub mod serve;
+pub mod solution;
+pub mod submit;
+#[cfg(test)]
+mod testing;
+pub mod verify;
+pub mod watch;
@@ -0,0 +1,69 @@
+use std::{
+    fs::File,
+    io::{self, IsTerminal},
+    path::Path,
+    sync::Mutex,
+};
+
+use tracing::level_filters::LevelFilter;
+use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer};
+
+/// The level for `-v` given `verbose` times, or `-q`: warnings by default,
+/// then info, debug and trace. Quiet keeps errors only.
+pub fn level(verbose: u8, quiet: bool) -> LevelFilter {
+    if quiet {
+        return LevelFilter::ERROR;
+    }
+    match verbose {
+        0 => LevelFilter::WARN,
+        1 => LevelFilter::INFO,
+        2 => LevelFilter::DEBUG,
+        _ => LevelFilter::TRACE,
+    }
+}
+
+/// Logs to stderr at `level`, and as JSON lines to `log_file` when given.
+/// `$RUST_LOG` directives, e.g. `adventofcode23::advent23::day5=trace`, are
+/// added on top.
+pub fn init(level: LevelFilter, log_file: Option<&Path>) -> io::Result<()> {
+    let filter = EnvFilter::builder()
+        .with_default_directive(level.into())
+        .from_env_lossy();
+    let stderr = fmt::layer()
+        .with_writer(io::stderr)
+        .with_ansi(io::stderr().is_terminal())
+        .without_time()
+        .with_target(false);
+    let file = match log_file {
+        Some(path) => Some(
+            fmt::layer()
+                .json()
+                .with_current_span(true)
+                .with_span_list(true)
+                .with_writer(Mutex::new(File::create(path)?))
+                .boxed(),
+        ),
+        None => None,
+    };
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(stderr)
+        .with(file)
+        .try_init()
+        .map_err(io::Error::other)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level() {
+        assert_eq!(LevelFilter::WARN, level(0, false));
+        assert_eq!(LevelFilter::INFO, level(1, false));
+        assert_eq!(LevelFilter::DEBUG, level(2, false));
+        assert_eq!(LevelFilter::TRACE, level(5, false));
+        assert_eq!(LevelFilter::ERROR, level(0, true));
+    }
+}
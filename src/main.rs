@@ -1,55 +1,884 @@
-mod advent23;
-
-use crate::advent23::{day1, day2, day3, day4, day5};
-
-fn main() {
-    println!("Running day 1 part 1!");
-    let res = day1::run("inputs/day1");
-    println!("Result of day 1 part 1: {}", res);
-    println!("");
-
-    println!("Running day 1 part 2!");
-    let res = day1::run_part2("inputs/day1");
-    println!("Result of day 1 part 2: {}", res);
-    println!("");
-
-    println!("Running day 2 part 1!");
-    let res = day2::run("inputs/day2");
-    println!("Result of day 1 part 1: {}", res);
-    println!("");
-
-    println!("Running day 2 part 2!");
-    let res = day2::run_part2("inputs/day2");
-    println!("Result of day 2 part 2: {}", res);
-    println!("");
-
-    println!("Running day 3 part 1!");
-    let res = day3::run("inputs/day3");
-    println!("Result of day 3 part 1: {}", res);
-    println!("");
-
-    println!("Running day 3 part 2!");
-    let res = day3::run_part2("inputs/day3");
-    println!("Result of day 3 part 2: {}", res);
-    println!("");
-
-    println!("Running day 4 part 1!");
-    let res = day4::run("inputs/day4");
-    println!("Result of day 4 part 1: {}", res);
-    println!("");
-
-    println!("Running day 4 part 2!");
-    let res = day4::run_part2("inputs/day4");
-    println!("Result of day 4 part 2: {}", res);
-    println!("");
-
-    println!("Running day 5 part 1!");
-    let res = day5::run("inputs/day5");
-    println!("Result of day 5 part 1: {}", res);
-    println!("");
-
-    println!("Running day 5 part 2!");
-    let res = day5::run_part2("inputs/day5");
-    println!("Result of day 5 part 2: {}", res);
-    println!("");
+mod cli;
+mod tui;
+
+use std::{
+    io::{self, Write},
+    path::Path,
+    process::ExitCode,
+    sync::Arc,
+    thread,
+    time::Duration,
+};
+
+use clap::{error::ErrorKind, CommandFactory, Parser};
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+
+use adventofcode23::{
+    answers::Answers,
+    bench::{self, format_duration, timed, Stats},
+    cache::{self, Cache},
+    cancel,
+    config::{self, Config, Loaded},
+    error::DayError,
+    fetch::{self, Fetched},
+    input::{self, Source},
+    logging, matrix,
+    memory::{self, Counting},
+    progress,
+    registry::{self, Year},
+    repl::{Repl, Reply},
+    report,
+    runner::{self, Options, Outcome, Record},
+    scaffold, serve,
+    solution::{DynSolution, Part},
+    submit::{self, History, SubmitError},
+    verify::{self, Tally, Verdict},
+    watch::Target,
+};
+
+use crate::cli::{
+    BenchArgs, CacheCommand, Cli, Command, ConfigCommand, DaySelection, FetchArgs, NewDayArgs,
+    ReplArgs, RunArgs, ServeArgs, SubmitArgs, TuiArgs, VerifyArgs, WatchArgs,
+};
+
+// Only counts once `run --mem` enables it.
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let level = logging::level(cli.verbose, cli.quiet);
+    if let Err(err) = logging::init(level, cli.log_file.as_deref()) {
+        eprintln!("error: failed to set up logging: {}", err);
+        return ExitCode::FAILURE;
+    }
+    let loaded = match Loaded::load() {
+        Ok(loaded) => loaded,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+    let config = &loaded.config;
+    if let Some(dir) = &config.input_dir {
+        input::configure_input_dir(dir.clone());
+    }
+
+    // Fetching and scaffolding also work for years without solutions yet.
+    match cli.command {
+        Command::Fetch(args) => {
+            let args = FetchArgs {
+                session_file: args.session_file.or(config.session_file.clone()),
+                ..args
+            };
+            return with_year_number(cli.year, |year| fetch(year, args));
+        }
+        Command::NewDay(args) => return with_year_number(cli.year, |year| new_day(year, args)),
+        Command::Cache(command) => return cache_command(command),
+        Command::Config(ConfigCommand::Show) => return show_config(&loaded),
+        _ => {}
+    }
+
+    let year = match registry::selected(cli.year) {
+        Ok(year) => year,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+    match cli.command {
+        Command::Run(args) => {
+            stop_on_ctrl_c();
+            let code = run(year, args.with_config(config), config.skip());
+            if cancel::interrupted() {
+                ExitCode::from(130)
+            } else {
+                code
+            }
+        }
+        Command::Bench(args) => bench(year, args, config.skip()),
+        Command::Verify(args) => verify(year, args, config.skip()),
+        Command::Watch(args) => watch(year, args),
+        Command::Repl(args) => repl(year, args),
+        Command::Tui(args) => dashboard(year, args),
+        Command::Serve(args) => serve(year, args.with_config(config)),
+        Command::Submit(args) => submit(
+            year,
+            SubmitArgs {
+                session_file: args.session_file.or(config.session_file.clone()),
+                ..args
+            },
+        ),
+        Command::Fetch(_) | Command::NewDay(_) | Command::Cache(_) | Command::Config(_) => {
+            unreachable!()
+        }
+    }
+}
+
+fn with_year_number(year: Option<u32>, f: impl FnOnce(u32) -> ExitCode) -> ExitCode {
+    match registry::year_number(year) {
+        Ok(year) => f(year),
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// The first Ctrl-C stops the running parts and skips the remaining ones, so
+/// the results so far are still reported. The second one exits right away.
+fn stop_on_ctrl_c() {
+    let res = ctrlc::set_handler(|| {
+        if cancel::interrupted() {
+            std::process::exit(130);
+        }
+        cancel::interrupt();
+        eprintln!("interrupted, stopping the running parts, press Ctrl-C again to quit");
+    });
+    if let Err(err) = res {
+        eprintln!("warning: failed to handle Ctrl-C: {}", err);
+    }
+}
+
+/// Registered solutions of `year` for `selection`, every one of them but the
+/// `skip`ped ones when it is `None`.
+fn select(
+    year: &Year,
+    selection: Option<&DaySelection>,
+    input: Option<&Path>,
+    skip: &[u32],
+) -> Result<Vec<&'static dyn DynSolution>, ExitCode> {
+    let days = selection.map(DaySelection::days);
+    let solutions = year.select(days, skip).map_err(|err| {
+        eprintln!("error: {}", err);
+        ExitCode::FAILURE
+    })?;
+
+    if let Some(path) = input {
+        if solutions.len() > 1 && !path.is_dir() {
+            Cli::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    "--input must be a directory when running more than one day",
+                )
+                .exit();
+        }
+    }
+
+    Ok(solutions)
+}
+
+fn run(year: &Year, args: RunArgs, skip: &[u32]) -> ExitCode {
+    let selection = if args.all { None } else { args.day.as_ref() };
+    let solutions = match select(year, selection, args.input.as_deref(), skip) {
+        Ok(solutions) => solutions,
+        Err(code) => return code,
+    };
+    if args.mem {
+        memory::enable();
+    }
+    if args.example {
+        return examples(&solutions, &args.parts(), args.timeout);
+    }
+
+    let cache = if args.no_cache {
+        None
+    } else {
+        match Cache::load(&args.cache_file, year.year) {
+            Ok(cache) => Some(cache),
+            Err(err) => {
+                eprintln!(
+                    "warning: ignoring the cache in {}: {}",
+                    args.cache_file.display(),
+                    err
+                );
+                None
+            }
+        }
+    };
+    let options = Options {
+        timeout: args.timeout,
+        cache: cache.as_ref(),
+    };
+
+    let code = match &args.inputs_dir {
+        Some(dir) => input_matrix(&solutions, dir, &args.parts(), args.jobs(), &options),
+        None => run_days(year, &args, solutions, &options),
+    };
+    if let Some(cache) = &cache {
+        if let Err(err) = cache.save(&args.cache_file) {
+            eprintln!(
+                "warning: failed to write {}: {}",
+                args.cache_file.display(),
+                err
+            );
+        }
+    }
+    code
+}
+
+fn run_days(
+    year: &Year,
+    args: &RunArgs,
+    solutions: Vec<&'static dyn DynSolution>,
+    options: &Options,
+) -> ExitCode {
+    let progress = MultiProgress::new();
+    progress::attach(&progress);
+    let style = ProgressStyle::with_template("{spinner} day {prefix:>2} {wide_msg}")
+        .expect("valid progress template");
+    let tasks: Vec<_> = solutions
+        .into_iter()
+        .map(|solution| {
+            let bar = ProgressBar::new_spinner()
+                .with_style(style.clone())
+                .with_prefix(solution.day().to_string())
+                .with_message(format!("{}: queued", solution.name()));
+            (solution, progress.add(bar))
+        })
+        .collect();
+
+    let stdout = io::stdout();
+    let mut reporter = report::reporter(args.format(), memory::is_enabled(), stdout.lock());
+    let parts = args.parts();
+    let mut failed = false;
+    let mut write_error = None;
+    let res = runner::run_ordered(
+        &tasks,
+        args.jobs(),
+        |(solution, bar)| {
+            bar.set_message(format!("{}: running", solution.name()));
+            bar.enable_steady_tick(Duration::from_millis(100));
+            let source = input::resolve(year.year, solution.day(), args.input.as_deref());
+            let (records, elapsed) = timed(|| runner::run_day(*solution, &source, &parts, options));
+            let status = if records.iter().all(|r| r.answer().is_some()) {
+                "done"
+            } else {
+                "failed"
+            };
+            bar.finish_with_message(format!(
+                "{}: {} in {}",
+                solution.name(),
+                status,
+                format_duration(elapsed)
+            ));
+            records
+        },
+        |records| {
+            if write_error.is_some() {
+                return;
+            }
+            progress.suspend(|| {
+                failed |= records.iter().any(|r| r.answer().is_none());
+                print_errors(&records);
+                for record in &records {
+                    if let Err(err) = reporter.record(record) {
+                        write_error = Some(err);
+                        return;
+                    }
+                }
+            });
+        },
+    );
+    let _ = progress.clear();
+
+    if let Err(err) = res {
+        eprintln!("error: failed to start {} jobs: {}", args.jobs(), err);
+        return ExitCode::FAILURE;
+    }
+    if let Some(err) = write_error {
+        eprintln!("error: failed to write results: {}", err);
+        return ExitCode::FAILURE;
+    }
+
+    if let Err(err) = reporter.finish() {
+        eprintln!("error: failed to write results: {}", err);
+        return ExitCode::FAILURE;
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn bench(year: &Year, args: BenchArgs, skip: &[u32]) -> ExitCode {
+    let solutions = match select(year, args.day.as_ref(), args.input.as_deref(), skip) {
+        Ok(solutions) => solutions,
+        Err(code) => return code,
+    };
+    let parts = args.parts();
+
+    println!(
+        "{:>4} {:>5}  {:<5} {:>10} {:>10} {:>10} {:>10}",
+        "day", "part", "phase", "min", "median", "mean", "p95"
+    );
+    let print_row = |day: u32, part: &str, phase: &str, stats: &Stats| {
+        println!(
+            "{:>4} {:>5}  {:<5} {:>10} {:>10} {:>10} {:>10}",
+            day,
+            part,
+            phase,
+            format_duration(stats.min),
+            format_duration(stats.median),
+            format_duration(stats.mean),
+            format_duration(stats.p95)
+        );
+    };
+
+    let mut failed = false;
+    let mut total = Duration::ZERO;
+    for solution in solutions {
+        let source = input::resolve(year.year, solution.day(), args.input.as_deref());
+        let path = source.to_string();
+        let input = match input::load(&source) {
+            Ok(input) => input,
+            Err(err) => {
+                eprintln!("{}", DayError::from(err).render(&path));
+                failed = true;
+                continue;
+            }
+        };
+
+        let stats = match bench::bench_day(solution, &input, &parts, args.warmup, args.iterations) {
+            Ok(stats) => stats,
+            Err(err) => {
+                println!("{:>4} {:>5}  {:<5} failed", solution.day(), "-", "-");
+                eprint!("{}", err.render(&path));
+                failed = true;
+                continue;
+            }
+        };
+
+        print_row(solution.day(), "-", "parse", &stats.parse);
+        for (part, part_stats) in &stats.parts {
+            print_row(solution.day(), &part.to_string(), "solve", part_stats);
+        }
+        total += stats.total();
+    }
+
+    println!();
+    println!("total (sum of medians): {}", format_duration(total));
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn verify(year: &Year, args: VerifyArgs, skip: &[u32]) -> ExitCode {
+    let solutions = match select(year, args.day.as_ref(), args.input.as_deref(), skip) {
+        Ok(solutions) => solutions,
+        Err(code) => return code,
+    };
+    let mut answers = match Answers::load(&args.answers) {
+        Ok(answers) => answers,
+        Err(err) => {
+            eprintln!("error: failed to read {}: {}", args.answers.display(), err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut tally = Tally::default();
+    let mut recorded = Vec::new();
+    for solution in solutions {
+        let source = input::resolve(year.year, solution.day(), args.input.as_deref());
+        let records = runner::run_day(solution, &source, &args.parts(), &Options::default());
+
+        for record in &records {
+            let verdict = verify::check(&answers, year.year, record);
+            print_verdict(
+                &verdict,
+                &format!("day {} part {}", record.day, record.part),
+                record,
+            );
+            tally.add(&verdict);
+
+            if let Some(answer) = record.answer() {
+                recorded.push((record.day, record.part, answer.to_string()));
+            }
+        }
+        print_errors(&records);
+    }
+
+    println!();
+    println!("{}", tally);
+
+    if args.record {
+        let count = recorded.len();
+        for (day, part, answer) in recorded {
+            answers.set(year.year, day, part, answer);
+        }
+        if let Err(err) = answers.save(&args.answers) {
+            eprintln!("error: failed to write {}: {}", args.answers.display(), err);
+            return ExitCode::FAILURE;
+        }
+        println!("Recorded {} answers to {}", count, args.answers.display());
+    }
+
+    if tally.is_failure(args.record) {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// Runs `solutions` against every input in `dir`, one matrix per day.
+fn input_matrix(
+    solutions: &[&dyn DynSolution],
+    dir: &Path,
+    parts: &[Part],
+    jobs: usize,
+    options: &Options,
+) -> ExitCode {
+    let inputs = match matrix::scan(dir) {
+        Ok(inputs) => inputs,
+        Err(err) => {
+            eprintln!("error: failed to read {}: {}", dir.display(), err);
+            return ExitCode::FAILURE;
+        }
+    };
+    if inputs.is_empty() {
+        eprintln!("error: no inputs in {}", dir.display());
+        return ExitCode::FAILURE;
+    }
+
+    let mut failed = false;
+    for (i, &solution) in solutions.iter().enumerate() {
+        let res = match matrix::run(solution, &inputs, parts, jobs, options) {
+            Ok(res) => res,
+            Err(err) => {
+                eprintln!("error: failed to start {} jobs: {}", jobs, err);
+                return ExitCode::FAILURE;
+            }
+        };
+
+        if i > 0 {
+            println!();
+        }
+        println!("Day {}: {}", solution.day(), solution.name());
+        print!("{}", matrix::render(parts, &res.rows));
+
+        for records in &res.records {
+            print_errors(records);
+        }
+        failed |= res.failed;
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// Renders the errors of `records` of one run. A parse failure is shared by
+/// both parts, so it is shown once; stopped parts are reported by their status.
+fn print_errors(records: &[Record]) {
+    let mut reported: Option<&Arc<DayError>> = None;
+    for record in records {
+        if let Outcome::Failed(err) = &record.outcome {
+            if !reported.is_some_and(|r| Arc::ptr_eq(r, err)) && record.cancelled().is_none() {
+                eprint!("{}", err.render(&record.source));
+                reported = Some(err);
+            }
+        }
+    }
+}
+
+fn print_verdict(verdict: &Verdict, label: &str, record: &Record) {
+    match verdict {
+        Verdict::Fail { expected, actual } => {
+            println!("{:<8} {}", verdict, label);
+            println!("{:<8} - expected: {}", "", expected);
+            println!("{:<8} + actual:   {}", "", actual);
+        }
+        Verdict::Missing => println!(
+            "{:<8} {} (got {})",
+            verdict,
+            label,
+            record.answer().map(|a| a.to_string()).unwrap_or_default()
+        ),
+        Verdict::Pass => println!("{:<8} {}", verdict, label),
+        // Says TIMEOUT or INTERRUPTED for stopped parts.
+        Verdict::Error => println!("{:<8} {}", record.status().to_uppercase(), label),
+    }
+}
+
+/// Runs the registered examples of `solutions`, only the parts with a known answer.
+fn examples(solutions: &[&dyn DynSolution], parts: &[Part], timeout: Option<Duration>) -> ExitCode {
+    let (mut passed, mut failed) = (0, 0);
+    for &solution in solutions {
+        if solution.examples().is_empty() {
+            println!("{:<8} day {}", "MISSING", solution.day());
+            continue;
+        }
+
+        for run in verify::examples(solution, parts, timeout) {
+            for (record, verdict) in run.records.iter().zip(&run.verdicts) {
+                let label = format!(
+                    "day {} part {} ({})",
+                    record.day,
+                    record.part,
+                    run.example.name()
+                );
+                print_verdict(verdict, &label, record);
+                match verdict {
+                    Verdict::Pass => passed += 1,
+                    _ => failed += 1,
+                }
+            }
+            print_errors(&run.records);
+        }
+    }
+
+    println!();
+    println!("{} passed, {} failed", passed, failed);
+
+    if failed > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn watch_run(target: &mut Target, solution: &dyn DynSolution, parts: &[Part]) {
+    let (lines, records) = target.run(solution, parts);
+    for line in lines {
+        println!("{}", line);
+    }
+    print_errors(&records);
+}
+
+fn watch(year: &Year, args: WatchArgs) -> ExitCode {
+    let Some(solution) = year.get(args.day) else {
+        eprintln!(
+            "error: no solution registered for {} day {}",
+            year.year, args.day
+        );
+        return ExitCode::FAILURE;
+    };
+    let path = match input::resolve(year.year, args.day, args.input.as_deref()) {
+        Source::File(path) => path,
+        Source::Stdin => Cli::command()
+            .error(
+                ErrorKind::ValueValidation,
+                "cannot watch stdin, pass a file",
+            )
+            .exit(),
+    };
+
+    let mut targets = vec![Target::input(path)];
+    targets.extend(solution.examples().iter().map(Target::example));
+
+    let parts = args.parts();
+    println!("Day {}: {}", solution.day(), solution.name());
+    for target in &mut targets {
+        watch_run(target, solution, &parts);
+    }
+    println!();
+    println!("Watching {} files, press Ctrl-C to stop", targets.len());
+
+    loop {
+        thread::sleep(Duration::from_millis(args.interval));
+        for target in &mut targets {
+            if target.file.poll() {
+                println!();
+                watch_run(target, solution, &parts);
+            }
+        }
+    }
+}
+
+fn repl(year: &Year, args: ReplArgs) -> ExitCode {
+    let Some(solution) = year.get(args.day) else {
+        eprintln!(
+            "error: no solution registered for {} day {}",
+            year.year, args.day
+        );
+        return ExitCode::FAILURE;
+    };
+
+    let (source, input) = if args.example {
+        let Some(example) = solution.examples().first() else {
+            eprintln!("error: day {} has no example", args.day);
+            return ExitCode::FAILURE;
+        };
+        (example.path.to_string(), example.input.to_string())
+    } else {
+        let source = input::resolve(year.year, args.day, args.input.as_deref());
+        if let Source::Stdin = source {
+            Cli::command()
+                .error(
+                    ErrorKind::ValueValidation,
+                    "the REPL reads commands from stdin, pass an input file",
+                )
+                .exit();
+        }
+        match input::load(&source) {
+            Ok(input) => (source.to_string(), input),
+            Err(err) => {
+                eprint!("{}", DayError::from(err).render(&source.to_string()));
+                return ExitCode::FAILURE;
+            }
+        }
+    };
+
+    let (repl, parse_time) = timed(|| Repl::new(solution, &input));
+    let mut repl = match repl {
+        Ok(repl) => repl,
+        Err(err) => {
+            eprint!("{}", err.render(&source));
+            return ExitCode::FAILURE;
+        }
+    };
+    println!(
+        "Day {}: {}, parsed {} in {}, `help` lists the commands",
+        solution.day(),
+        solution.name(),
+        source,
+        format_duration(parse_time)
+    );
+
+    let stdin = io::stdin();
+    let mut line = String::new();
+    loop {
+        print!("{}", repl.prompt());
+        let _ = io::stdout().flush();
+        line.clear();
+        match stdin.read_line(&mut line) {
+            // Ctrl-D
+            Ok(0) => {
+                println!();
+                return ExitCode::SUCCESS;
+            }
+            Ok(_) => {}
+            Err(err) => {
+                eprintln!("error: failed to read the command: {}", err);
+                return ExitCode::FAILURE;
+            }
+        }
+
+        match repl.eval(&line) {
+            Reply::Output(output) if output.is_empty() => {}
+            Reply::Output(output) => println!("{}", output),
+            Reply::Quit => return ExitCode::SUCCESS,
+        }
+    }
+}
+
+fn dashboard(year: &Year, args: TuiArgs) -> ExitCode {
+    if args.input.as_deref().is_some_and(|input| !input.is_dir()) {
+        Cli::command()
+            .error(
+                ErrorKind::ValueValidation,
+                "the dashboard runs every day, pass a directory of inputs",
+            )
+            .exit();
+    }
+    let answers = match Answers::load(&args.answers) {
+        Ok(answers) => answers,
+        Err(err) => {
+            eprintln!("error: failed to read {}: {}", args.answers.display(), err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match tui::run(year, &answers, args.input.as_deref()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: terminal: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn serve(year: &'static Year, args: ServeArgs) -> ExitCode {
+    eprintln!(
+        "serving {} on http://127.0.0.1:{}, `GET /days` lists the days",
+        year.year, args.port
+    );
+    match serve::serve(year, args.port, args.timeout) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: failed to serve on port {}: {}", args.port, err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn fetch(year: u32, args: FetchArgs) -> ExitCode {
+    let dir = args.dir.unwrap_or_else(|| input::year_dir(year));
+    let mut days = Vec::new();
+    for &day in args.day.days() {
+        match fetch::existing(&dir, day) {
+            Some(path) => println!("day {}: {} already exists", day, path.display()),
+            None => days.push(day),
+        }
+    }
+    if days.is_empty() {
+        return ExitCode::SUCCESS;
+    }
+
+    let session_file = args.session_file.or_else(fetch::session_file);
+    let session = match fetch::session(session_file.as_deref()) {
+        Ok(session) => session,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+    let client = fetch::Client::new(&fetch::base_url(args.base_url.as_deref()), &session);
+
+    let mut failed = false;
+    for day in days {
+        match fetch::fetch(&client, year, day, &dir) {
+            Ok(Fetched::Downloaded(path)) => println!("day {}: saved {}", day, path.display()),
+            Ok(Fetched::Existing(path)) => {
+                println!("day {}: {} already exists", day, path.display())
+            }
+            Err(err) => {
+                eprintln!("error: day {}: {}", day, err);
+                failed = true;
+            }
+        }
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn submit(year: &Year, args: SubmitArgs) -> ExitCode {
+    let Some(solution) = year.get(args.day) else {
+        eprintln!(
+            "error: no solution registered for {} day {}",
+            year.year, args.day
+        );
+        return ExitCode::FAILURE;
+    };
+    let source = input::resolve(year.year, args.day, args.input.as_deref());
+    let records = runner::run_day(solution, &source, &[args.part], &Options::default());
+    let answer = match &records[0].outcome {
+        Outcome::Solved(answer) => answer.to_string(),
+        Outcome::Failed(err) => {
+            eprint!("{}", err.render(&records[0].source));
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut history = match History::load(&args.history) {
+        Ok(history) => history,
+        Err(err) => {
+            eprintln!("error: failed to read {}: {}", args.history.display(), err);
+            return ExitCode::FAILURE;
+        }
+    };
+    let session_file = args.session_file.or_else(fetch::session_file);
+    let client = || {
+        let session = fetch::session(session_file.as_deref())?;
+        Ok(fetch::Client::new(
+            &fetch::base_url(args.base_url.as_deref()),
+            &session,
+        ))
+    };
+    let res = submit::submit(
+        &mut history,
+        client,
+        year.year,
+        args.day,
+        args.part,
+        &answer,
+    );
+    let outcome = match res {
+        Ok(outcome) => outcome,
+        Err(SubmitError::Refused(refusal)) => {
+            eprintln!(
+                "error: not submitting {} for day {} part {}: {}",
+                answer, args.day, args.part, refusal
+            );
+            return ExitCode::FAILURE;
+        }
+        Err(err) => {
+            eprintln!("error: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    println!(
+        "day {} part {}: {} is {}",
+        args.day, args.part, answer, outcome
+    );
+    if let Err(err) = history.save(&args.history) {
+        eprintln!("error: failed to write {}: {}", args.history.display(), err);
+        return ExitCode::FAILURE;
+    }
+    if outcome != submit::Outcome::Correct {
+        return ExitCode::FAILURE;
+    }
+
+    let recorded = Answers::load(&args.answers).and_then(|mut answers| {
+        answers.set(year.year, args.day, args.part, answer);
+        answers.save(&args.answers)
+    });
+    if let Err(err) = recorded {
+        eprintln!("error: failed to write {}: {}", args.answers.display(), err);
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
+
+fn new_day(year: u32, args: NewDayArgs) -> ExitCode {
+    let name = args.name.unwrap_or_else(|| format!("Day {}", args.day));
+    match scaffold::create(&args.root, &input::year_dir(year), year, args.day, &name) {
+        Ok(written) => {
+            for path in written {
+                println!("wrote {}", path.display());
+            }
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("error: cannot add day {}: {}", args.day, err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Prints the config files merged, with the defaults and environment filled
+/// in.
+fn show_config(loaded: &Loaded) -> ExitCode {
+    let config = &loaded.config;
+    let effective = Config {
+        input_dir: Some(input::input_dir()),
+        format: Some(config.format.unwrap_or_default()),
+        jobs: Some(config.jobs.unwrap_or(1)),
+        timeout: config.timeout,
+        session_file: config.session_file.clone().or_else(fetch::session_file),
+        skip: Some(config.skip().to_vec()),
+    };
+    print!("{}", config::render(&effective, &loaded.files));
+    ExitCode::SUCCESS
+}
+
+fn cache_command(command: CacheCommand) -> ExitCode {
+    match command {
+        CacheCommand::Clear { cache_file } => match cache::clear(&cache_file) {
+            Ok(true) => {
+                println!("removed {}", cache_file.display());
+                ExitCode::SUCCESS
+            }
+            Ok(false) => {
+                println!("no cache at {}", cache_file.display());
+                ExitCode::SUCCESS
+            }
+            Err(err) => {
+                eprintln!("error: failed to remove {}: {}", cache_file.display(), err);
+                ExitCode::FAILURE
+            }
+        },
+    }
 }
@@ -0,0 +1,266 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use rayon::ThreadPoolBuildError;
+
+use crate::{
+    input::Source,
+    runner::{self, Options, Record},
+    solution::{DynSolution, Part},
+    verify::{self, Verdict},
+};
+
+pub const ANSWER_EXTENSION: &str = "answer";
+
+/// One input of a directory, with the answers expected for it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Input {
+    pub name: String,
+    pub path: PathBuf,
+    expected: [Option<String>; 2],
+}
+
+impl Input {
+    pub fn expected(&self, part: Part) -> Option<&str> {
+        self.expected[part.number() as usize - 1].as_deref()
+    }
+}
+
+/// The inputs in `dir` sorted by name, each paired with the `name.answer`
+/// file next to it, if there is one.
+///
+/// A name is the file name up to the first `.`, so `alice.txt.gz` pairs with
+/// `alice.answer`. Hidden files are skipped.
+pub fn scan(dir: &Path) -> io::Result<Vec<Input>> {
+    let mut inputs = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        let is_answer = path.extension().is_some_and(|ext| ext == ANSWER_EXTENSION);
+        if file_name.starts_with('.') || is_answer || !path.is_file() {
+            continue;
+        }
+
+        let name = file_name.split('.').next().unwrap_or(file_name).to_string();
+        let answer_path = dir.join(format!("{}.{}", name, ANSWER_EXTENSION));
+        let expected = match fs::read_to_string(&answer_path) {
+            Ok(content) => parse_answers(&content),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Default::default(),
+            Err(err) => return Err(err),
+        };
+        inputs.push(Input {
+            name,
+            path,
+            expected,
+        });
+    }
+
+    inputs.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.path.cmp(&b.path)));
+    Ok(inputs)
+}
+
+/// The first line is the answer of part 1, the second the one of part 2. An
+/// empty line leaves a part without an expected answer.
+pub fn parse_answers(content: &str) -> [Option<String>; 2] {
+    let mut lines = content.lines().map(|line| {
+        let line = line.trim();
+        (!line.is_empty()).then(|| line.to_string())
+    });
+    [lines.next().flatten(), lines.next().flatten()]
+}
+
+/// The answer in `record` and how it compares to the expected one.
+pub fn cell(record: &Record, verdict: &Verdict) -> String {
+    let answer = record.answer().map(|a| a.to_string()).unwrap_or_default();
+    match verdict {
+        Verdict::Pass => format!("{} PASS", answer),
+        Verdict::Fail { expected, .. } => format!("{} FAIL, expected {}", answer, expected),
+        Verdict::Missing => answer,
+        Verdict::Error => record.status().to_uppercase(),
+    }
+}
+
+/// The runs of one day against every input.
+#[derive(Debug)]
+pub struct Matrix {
+    /// One row of cells per input, see [`render`].
+    pub rows: Vec<(String, Vec<String>)>,
+    /// The records of each input, in the order of the rows.
+    pub records: Vec<Vec<Record>>,
+    /// Whether an answer was wrong or a part did not finish.
+    pub failed: bool,
+}
+
+/// Runs `solution` against every one of `inputs` on `jobs` threads.
+pub fn run(
+    solution: &dyn DynSolution,
+    inputs: &[Input],
+    parts: &[Part],
+    jobs: usize,
+    options: &Options,
+) -> Result<Matrix, ThreadPoolBuildError> {
+    let mut records = Vec::new();
+    runner::run_ordered(
+        inputs,
+        jobs,
+        |input| {
+            let source = Source::File(input.path.clone());
+            runner::run_day(solution, &source, parts, options)
+        },
+        |res| records.push(res),
+    )?;
+
+    let mut failed = false;
+    let rows = inputs
+        .iter()
+        .zip(&records)
+        .map(|(input, records)| {
+            let cells = records
+                .iter()
+                .map(|record| {
+                    let verdict = verify::compare(input.expected(record.part), record);
+                    failed |= matches!(verdict, Verdict::Fail { .. } | Verdict::Error);
+                    cell(record, &verdict)
+                })
+                .collect();
+            (input.name.clone(), cells)
+        })
+        .collect();
+
+    Ok(Matrix {
+        rows,
+        records,
+        failed,
+    })
+}
+
+/// Lays out one row per input and one column per part.
+pub fn render(parts: &[Part], rows: &[(String, Vec<String>)]) -> String {
+    let mut header = vec!["input".to_string()];
+    header.extend(parts.iter().map(|part| format!("part {}", part)));
+
+    let table: Vec<Vec<&str>> = std::iter::once(header.iter().map(String::as_str).collect())
+        .chain(rows.iter().map(|(name, cells)| {
+            std::iter::once(name.as_str())
+                .chain(cells.iter().map(String::as_str))
+                .collect()
+        }))
+        .collect();
+
+    let columns = header.len();
+    let widths: Vec<usize> = (0..columns)
+        .map(|i| {
+            table
+                .iter()
+                .filter_map(|row| row.get(i))
+                .map(|cell| cell.chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let mut out = String::new();
+    for row in &table {
+        let line: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, &width)| format!("{:<width$}", cell, width = width))
+            .collect();
+        out.push_str(line.join("  ").trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_answers() {
+        assert_eq!(
+            [Some("1".to_string()), Some("2".to_string())],
+            parse_answers("1\n2\n")
+        );
+        assert_eq!([None, Some("2".to_string())], parse_answers("\n 2 \n"));
+        assert_eq!([Some("1".to_string()), None], parse_answers("1"));
+        assert_eq!([None, None], parse_answers(""));
+    }
+
+    #[test]
+    fn test_scan() {
+        let dir = env::temp_dir().join(format!("aoc-matrix-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("bob.txt"), "").unwrap();
+        fs::write(dir.join("alice.gz"), "").unwrap();
+        fs::write(dir.join("alice.answer"), "55488\n55614\n").unwrap();
+        fs::write(dir.join(".hidden"), "").unwrap();
+
+        let res = scan(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let names: Vec<&str> = res.iter().map(|input| input.name.as_str()).collect();
+        assert_eq!(vec!["alice", "bob"], names);
+        assert_eq!(dir.join("alice.gz"), res[0].path);
+        assert_eq!(Some("55614"), res[0].expected(Part::Two));
+        assert_eq!(None, res[1].expected(Part::One));
+    }
+
+    #[test]
+    fn test_run() {
+        let dir = crate::testing::temp_dir("matrix-run");
+        let example = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green\n";
+        fs::write(dir.join("alice.txt"), example).unwrap();
+        fs::write(dir.join("alice.answer"), "1\n48\n").unwrap();
+        fs::write(dir.join("bob.txt"), example).unwrap();
+        fs::write(dir.join("bob.answer"), "2\n").unwrap();
+        fs::write(dir.join("carol.txt"), "Game 1: 3 teal\n").unwrap();
+
+        let inputs = scan(&dir).unwrap();
+        let res = run(
+            &crate::advent23::day2::Day2,
+            &inputs,
+            &Part::ALL,
+            2,
+            &Options::default(),
+        )
+        .unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!("alice", res.rows[0].0);
+        assert_eq!(vec!["1 PASS", "48 PASS"], res.rows[0].1);
+        assert_eq!(vec!["1 FAIL, expected 2", "48"], res.rows[1].1);
+        assert_eq!(vec!["ERROR", "ERROR"], res.rows[2].1);
+        assert_eq!(3, res.records.len());
+        assert!(res.failed);
+    }
+
+    #[test]
+    fn test_render() {
+        let rows = vec![
+            (
+                "alice".to_string(),
+                vec!["142 PASS".to_string(), "281 PASS".to_string()],
+            ),
+            (
+                "bob".to_string(),
+                vec!["1 FAIL, expected 2".to_string(), "ERROR".to_string()],
+            ),
+        ];
+
+        assert_eq!(
+            "input  part 1              part 2
+alice  142 PASS            281 PASS
+bob    1 FAIL, expected 2  ERROR
+",
+            render(&Part::ALL, &rows)
+        );
+    }
+}
@@ -0,0 +1,169 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    fmt,
+    sync::atomic::{AtomicBool, AtomicI64, AtomicU64, Ordering::Relaxed},
+};
+
+use serde::Serialize;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static ALLOCATED: AtomicU64 = AtomicU64::new(0);
+// Signed, memory allocated before counting started may be freed afterwards.
+static LIVE: AtomicI64 = AtomicI64::new(0);
+static PEAK: AtomicI64 = AtomicI64::new(0);
+
+/// Global allocator that counts allocations once [`enable`]d and otherwise
+/// only forwards to the system allocator. Reallocations count as
+/// allocations.
+///
+/// Installed by the binary with `#[global_allocator]`.
+pub struct Counting;
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            allocated(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            allocated(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        freed(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new = System.realloc(ptr, layout, new_size);
+        if !new.is_null() {
+            freed(layout.size());
+            allocated(new_size);
+        }
+        new
+    }
+}
+
+fn allocated(size: usize) {
+    if ENABLED.load(Relaxed) {
+        ALLOCATIONS.fetch_add(1, Relaxed);
+        ALLOCATED.fetch_add(size as u64, Relaxed);
+        let live = LIVE.fetch_add(size as i64, Relaxed) + size as i64;
+        PEAK.fetch_max(live, Relaxed);
+    }
+}
+
+fn freed(size: usize) {
+    if ENABLED.load(Relaxed) {
+        LIVE.fetch_sub(size as i64, Relaxed);
+    }
+}
+
+/// Starts counting, which only has an effect when [`Counting`] is the
+/// global allocator.
+pub fn enable() {
+    ENABLED.store(true, Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Relaxed)
+}
+
+/// Allocations made while running something.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub struct Usage {
+    pub allocations: u64,
+    pub bytes: u64,
+    /// Highest number of bytes live at once, on top of what was live before.
+    pub peak: u64,
+}
+
+impl fmt::Display for Usage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} allocs, {}, peak {}",
+            self.allocations,
+            format_bytes(self.bytes),
+            format_bytes(self.peak)
+        )
+    }
+}
+
+/// Runs `f` once and returns its result together with the allocations it
+/// made, or `None` when counting is not enabled.
+///
+/// Counters are process wide, so anything else running at the same time is
+/// counted too.
+pub fn measured<T>(f: impl FnOnce() -> T) -> (T, Option<Usage>) {
+    if !is_enabled() {
+        return (f(), None);
+    }
+
+    let allocations = ALLOCATIONS.load(Relaxed);
+    let bytes = ALLOCATED.load(Relaxed);
+    let live = LIVE.load(Relaxed);
+    PEAK.store(live, Relaxed);
+    let res = f();
+    let usage = Usage {
+        allocations: ALLOCATIONS.load(Relaxed) - allocations,
+        bytes: ALLOCATED.load(Relaxed) - bytes,
+        peak: (PEAK.load(Relaxed) - live).max(0) as u64,
+    };
+    (res, Some(usage))
+}
+
+/// Formats `bytes` with a binary unit and three significant digits.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{}B", bytes);
+    }
+
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    let precision = if value < 10.0 {
+        2
+    } else if value < 100.0 {
+        1
+    } else {
+        0
+    };
+    format!("{:.*}{}", precision, value, UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!("0B", format_bytes(0));
+        assert_eq!("1023B", format_bytes(1023));
+        assert_eq!("1.00KiB", format_bytes(1024));
+        assert_eq!("52.5KiB", format_bytes(53_760));
+        assert_eq!("3.00MiB", format_bytes(3 * 1024 * 1024));
+    }
+
+    #[test]
+    fn test_usage_display() {
+        let usage = Usage {
+            allocations: 12,
+            bytes: 4096,
+            peak: 100,
+        };
+        assert_eq!("12 allocs, 4.00KiB, peak 100B", usage.to_string());
+    }
+}
@@ -0,0 +1,67 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Mutex,
+};
+
+use indicatif::{MultiProgress, ProgressBar};
+
+static HIDDEN: AtomicBool = AtomicBool::new(false);
+static CURRENT: Mutex<Option<ProgressBar>> = Mutex::new(None);
+static MULTI: Mutex<Option<MultiProgress>> = Mutex::new(None);
+
+/// Keeps the bars of [`bar`] from drawing, for hosts that show the progress
+/// of [`current`] themselves.
+pub fn hide() {
+    HIDDEN.store(true, Ordering::SeqCst);
+}
+
+/// Draws the bars of [`bar`] in `multi`, for hosts that draw bars of their
+/// own, so the two do not write over each other.
+pub fn attach(multi: &MultiProgress) {
+    *MULTI.lock().unwrap() = Some(multi.clone());
+}
+
+/// A progress bar of `len` steps for a long loop of a part, drawn on stderr,
+/// or in the [`attach`]ed bars, unless [`hide`]den.
+pub fn bar(len: u64) -> ProgressBar {
+    let bar = if HIDDEN.load(Ordering::SeqCst) {
+        let bar = ProgressBar::hidden();
+        bar.set_length(len);
+        bar
+    } else {
+        match MULTI.lock().unwrap().as_ref() {
+            Some(multi) => multi.add(ProgressBar::new(len)),
+            None => ProgressBar::new(len),
+        }
+    };
+    *CURRENT.lock().unwrap() = Some(bar.clone());
+    bar
+}
+
+/// Position and length of the latest bar.
+pub fn current() -> Option<(u64, u64)> {
+    let current = CURRENT.lock().unwrap();
+    let bar = current.as_ref()?;
+    Some((bar.position(), bar.length().unwrap_or(0)))
+}
+
+/// Forgets the latest bar, so the next part starts without one.
+pub fn reset() {
+    *CURRENT.lock().unwrap() = None;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_current() {
+        hide();
+        let bar = bar(10);
+        bar.inc(4);
+        assert_eq!(Some((4, 10)), current());
+
+        reset();
+        assert_eq!(None, current());
+    }
+}
@@ -0,0 +1,127 @@
+use std::env;
+
+use crate::{advent23, solution::DynSolution};
+
+pub const YEAR_ENV: &str = "AOC_YEAR";
+
+/// The solutions of one event.
+pub struct Year {
+    pub year: u32,
+    /// Ordered by day number.
+    pub solutions: &'static [&'static dyn DynSolution],
+}
+
+impl Year {
+    pub fn get(&self, day: u32) -> Option<&'static dyn DynSolution> {
+        self.solutions.iter().copied().find(|s| s.day() == day)
+    }
+
+    /// The solutions of `days`, every one of them but the `skip`ped ones
+    /// when it is `None`. Fails when a day has no solution.
+    pub fn select(
+        &self,
+        days: Option<&[u32]>,
+        skip: &[u32],
+    ) -> Result<Vec<&'static dyn DynSolution>, String> {
+        if let Some(days) = days {
+            let missing: Vec<String> = days
+                .iter()
+                .filter(|&&day| self.get(day).is_none())
+                .map(|day| day.to_string())
+                .collect();
+            if !missing.is_empty() {
+                return Err(format!(
+                    "no solution registered for {} day {}",
+                    self.year,
+                    missing.join(", ")
+                ));
+            }
+        }
+
+        Ok(self
+            .solutions
+            .iter()
+            .copied()
+            .filter(|s| match days {
+                Some(days) => days.contains(&s.day()),
+                None => !skip.contains(&s.day()),
+            })
+            .collect())
+    }
+}
+
+/// Every implemented year, oldest first.
+pub static YEARS: &[Year] = &[Year {
+    year: advent23::YEAR,
+    solutions: advent23::SOLUTIONS,
+}];
+
+pub fn get(year: u32) -> Option<&'static Year> {
+    YEARS.iter().find(|y| y.year == year)
+}
+
+/// The year to work on: `year` when given, then `AOC_YEAR`, then the latest
+/// registered one.
+pub fn year_number(year: Option<u32>) -> Result<u32, String> {
+    if let Some(year) = year {
+        return Ok(year);
+    }
+    match env::var(YEAR_ENV) {
+        Ok(year) if !year.is_empty() => year
+            .parse()
+            .map_err(|_| format!("invalid `{}` value `{}`", YEAR_ENV, year)),
+        _ => YEARS
+            .last()
+            .map(|y| y.year)
+            .ok_or_else(|| "no years registered".to_string()),
+    }
+}
+
+/// Like [`year_number`], but the year must have solutions.
+pub fn selected(year: Option<u32>) -> Result<&'static Year, String> {
+    let year = year_number(year)?;
+    get(year).ok_or_else(|| format!("no solutions registered for {}", year))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_years_are_ordered_and_unique() {
+        let years: Vec<u32> = YEARS.iter().map(|y| y.year).collect();
+        let mut sorted = years.clone();
+        sorted.sort_unstable();
+        sorted.dedup();
+        assert_eq!(sorted, years);
+    }
+
+    #[test]
+    fn test_get() {
+        let year = get(2023).unwrap();
+        assert_eq!(Some(3), year.get(3).map(|s| s.day()));
+        assert!(year.get(25).is_none());
+        assert!(get(2014).is_none());
+    }
+
+    #[test]
+    fn test_select() {
+        let year = get(2023).unwrap();
+        let days = |res: Vec<&dyn DynSolution>| res.iter().map(|s| s.day()).collect::<Vec<_>>();
+
+        assert_eq!(vec![1, 3], days(year.select(Some(&[3, 1]), &[1]).unwrap()));
+        let all = days(year.select(None, &[]).unwrap());
+        assert_eq!(year.solutions.len(), all.len());
+        assert!(!days(year.select(None, &[2]).unwrap()).contains(&2));
+        assert_eq!(
+            Err("no solution registered for 2023 day 24, 25".to_string()),
+            year.select(Some(&[1, 24, 25]), &[]).map(days)
+        );
+    }
+
+    #[test]
+    fn test_selected() {
+        assert_eq!(2023, selected(Some(2023)).unwrap().year);
+        assert!(selected(Some(2014)).is_err());
+    }
+}
@@ -0,0 +1,200 @@
+use std::any::Any;
+
+use crate::{
+    bench::{format_duration, timed},
+    error::DayError,
+    solution::{DynSolution, Part},
+};
+
+/// What to do after a line was evaluated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reply {
+    Output(String),
+    Quit,
+}
+
+/// Commands every day has, with their usage and help.
+const COMMANDS: [(&str, &str); 4] = [
+    ("part1, part2", "Solve a part"),
+    (
+        "history",
+        "List earlier commands, `!N` runs number N again and `!!` the last one",
+    ),
+    ("help", "Show this help"),
+    ("quit", "Leave, as does Ctrl-D"),
+];
+
+/// Keeps the parsed input of one day around to run commands against.
+pub struct Repl<'a> {
+    solution: &'a dyn DynSolution,
+    parsed: Box<dyn Any>,
+    history: Vec<String>,
+}
+
+impl<'a> Repl<'a> {
+    pub fn new(solution: &'a dyn DynSolution, input: &str) -> Result<Self, DayError> {
+        Ok(Self {
+            solution,
+            parsed: solution.parse(input)?,
+            history: Vec::new(),
+        })
+    }
+
+    pub fn prompt(&self) -> String {
+        format!("day{}> ", self.solution.day())
+    }
+
+    pub fn eval(&mut self, line: &str) -> Reply {
+        let line = line.trim();
+        if line.is_empty() {
+            return Reply::Output(String::new());
+        }
+
+        let (line, echo) = match self.expand(line) {
+            Ok(Some(expanded)) => (expanded, true),
+            Ok(None) => (line.to_string(), false),
+            Err(err) => return Reply::Output(err),
+        };
+        self.history.push(line.clone());
+        match self.run(&line) {
+            Reply::Output(output) if echo => Reply::Output(format!("{}\n{}", line, output)),
+            reply => reply,
+        }
+    }
+
+    /// The earlier command `!!` or `!N` refers to.
+    fn expand(&self, line: &str) -> Result<Option<String>, String> {
+        let Some(reference) = line.strip_prefix('!') else {
+            return Ok(None);
+        };
+        let found = match reference {
+            "!" => self.history.last(),
+            n => n
+                .parse::<usize>()
+                .ok()
+                .and_then(|n| self.history.get(n.checked_sub(1)?)),
+        };
+        match found {
+            Some(command) => Ok(Some(command.clone())),
+            None => Err(format!("no command `{}` in the history", line)),
+        }
+    }
+
+    fn run(&self, line: &str) -> Reply {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let Some((&command, args)) = words.split_first() else {
+            return Reply::Output(String::new());
+        };
+
+        let output = match command {
+            "part1" => self.solve(Part::One),
+            "part2" => self.solve(Part::Two),
+            "history" => self
+                .history
+                .iter()
+                .enumerate()
+                .map(|(i, command)| format!("{:>4}  {}", i + 1, command))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            "help" => self.help(),
+            "quit" | "exit" => return Reply::Quit,
+            name => match self.solution.queries().iter().find(|q| q.name == name) {
+                Some(query) => match self.solution.query(self.parsed.as_ref(), query, args) {
+                    Ok(output) => output,
+                    Err(err) => format!("error: {}", err),
+                },
+                None => format!("unknown command `{}`, try `help`", name),
+            },
+        };
+        Reply::Output(output)
+    }
+
+    fn solve(&self, part: Part) -> String {
+        let (res, elapsed) = timed(|| self.solution.solve(part, self.parsed.as_ref()));
+        match res {
+            Ok(answer) => format!("part {}: {} in {}", part, answer, format_duration(elapsed)),
+            Err(err) => format!("error: {}", err),
+        }
+    }
+
+    fn help(&self) -> String {
+        let queries = self.solution.queries();
+        let width = COMMANDS
+            .iter()
+            .map(|&(usage, _)| usage.len())
+            .chain(queries.iter().map(|q| q.usage.len()))
+            .max()
+            .unwrap_or(0);
+        let line = |usage: &str, help: &str| format!("  {:<width$}  {}", usage, help);
+
+        let mut lines = vec!["commands:".to_string()];
+        lines.extend(COMMANDS.iter().map(|&(usage, help)| line(usage, help)));
+        if queries.is_empty() {
+            lines.push(format!("day {} has no queries", self.solution.day()));
+        } else {
+            lines.push(format!("day {} queries:", self.solution.day()));
+            lines.extend(queries.iter().map(|q| line(q.usage, q.help)));
+        }
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::advent23::{day1::Day1, day5::Day5};
+
+    fn repl() -> Repl<'static> {
+        Repl::new(&Day5, Day5.examples()[0].input).unwrap()
+    }
+
+    fn output(reply: Reply) -> String {
+        match reply {
+            Reply::Output(output) => output,
+            Reply::Quit => panic!("unexpected quit"),
+        }
+    }
+
+    #[test]
+    fn test_eval() {
+        let mut repl = repl();
+        assert_eq!("day5> ", repl.prompt());
+        assert!(output(repl.eval("part1")).starts_with("part 1: 35 in "));
+        assert_eq!(
+            "seed 13 -> soil 13 -> fertilizer 52 -> water 41 -> light 34 -> temperature 34 -> humidity 35 -> location 35",
+            output(repl.eval("  trace 13 "))
+        );
+        assert_eq!("error: usage: trace <seed>", output(repl.eval("trace")));
+        assert_eq!(
+            "unknown command `nope`, try `help`",
+            output(repl.eval("nope"))
+        );
+        assert_eq!("", output(repl.eval("")));
+        assert_eq!(Reply::Quit, repl.eval("quit"));
+    }
+
+    #[test]
+    fn test_history() {
+        let mut repl = repl();
+        repl.eval("show seeds");
+        repl.eval("part1");
+
+        assert_eq!("show seeds\n79 14 55 13", output(repl.eval("!1")));
+        assert!(output(repl.eval("!!")).starts_with("show seeds\n"));
+        assert_eq!("no command `!9` in the history", output(repl.eval("!9")));
+        assert_eq!(
+            "   1  show seeds\n   2  part1\n   3  show seeds\n   4  show seeds\n   5  history",
+            output(repl.eval("history"))
+        );
+    }
+
+    #[test]
+    fn test_help() {
+        let help = output(repl().eval("help"));
+        assert!(help.contains("\nday 5 queries:\n"));
+        assert!(help.contains("  trace <seed>  "));
+
+        let mut repl = Repl::new(&Day1, "1abc2\n").unwrap();
+        assert!(output(repl.eval("help")).ends_with("day 1 has no queries"));
+    }
+}
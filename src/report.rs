@@ -0,0 +1,401 @@
+use std::{
+    io::{self, Write},
+    time::Duration,
+};
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bench::format_duration,
+    memory::{format_bytes, Usage},
+    runner::Record,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    #[default]
+    Text,
+    Json,
+    Csv,
+    Markdown,
+}
+
+pub trait Reporter {
+    fn record(&mut self, record: &Record) -> io::Result<()>;
+
+    fn finish(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// `memory` adds allocation columns to the CSV and Markdown tables, the text
+/// and JSON output show memory whenever a record has it.
+pub fn reporter<'a>(format: Format, memory: bool, out: impl Write + 'a) -> Box<dyn Reporter + 'a> {
+    match format {
+        Format::Text => Box::new(Text { out }),
+        Format::Json => Box::new(Json {
+            out,
+            rows: Vec::new(),
+        }),
+        Format::Csv => Box::new(Csv {
+            out,
+            memory,
+            header: false,
+        }),
+        Format::Markdown => Box::new(Markdown {
+            out,
+            memory,
+            header: false,
+        }),
+    }
+}
+
+/// Flat, serializable view of a [`Record`].
+#[derive(Debug, Serialize)]
+pub(crate) struct Row {
+    day: u32,
+    part: u8,
+    name: &'static str,
+    answer: Option<String>,
+    status: &'static str,
+    error: Option<String>,
+    parse_ms: f64,
+    solve_ms: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parse_memory: Option<Usage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    solve_memory: Option<Usage>,
+}
+
+impl From<&Record> for Row {
+    fn from(record: &Record) -> Self {
+        Self {
+            day: record.day,
+            part: record.part.number(),
+            name: record.name,
+            answer: record.answer().map(|a| a.to_string()),
+            status: record.status(),
+            error: record.error().map(|e| e.to_string()),
+            parse_ms: millis(record.parse_time),
+            solve_ms: millis(record.solve_time),
+            parse_memory: record.parse_memory,
+            solve_memory: record.solve_memory,
+        }
+    }
+}
+
+/// Milliseconds with microsecond precision, which keeps the JSON readable.
+fn millis(duration: Duration) -> f64 {
+    duration.as_micros() as f64 / 1e3
+}
+
+struct Text<W> {
+    out: W,
+}
+
+impl<W: Write> Reporter for Text<W> {
+    fn record(&mut self, record: &Record) -> io::Result<()> {
+        writeln!(
+            self.out,
+            "Running day {} part {}: {}",
+            record.day, record.part, record.name
+        )?;
+        match record.answer() {
+            Some(answer) if record.cached => writeln!(
+                self.out,
+                "Result of day {} part {}: {} (cached)",
+                record.day, record.part, answer
+            )?,
+            Some(answer) => writeln!(
+                self.out,
+                "Result of day {} part {}: {} (parse {}, solve {})",
+                record.day,
+                record.part,
+                answer,
+                format_duration(record.parse_time),
+                format_duration(record.solve_time)
+            )?,
+            None => match record.cancelled() {
+                Some(reason) => writeln!(
+                    self.out,
+                    "Day {} part {} {} after {}",
+                    record.day,
+                    record.part,
+                    reason,
+                    format_duration(record.solve_time)
+                )?,
+                None => writeln!(self.out, "Day {} part {} failed", record.day, record.part)?,
+            },
+        }
+        if let (Some(parse), Some(solve)) = (record.parse_memory, record.solve_memory) {
+            writeln!(
+                self.out,
+                "Memory of day {} part {}: parse {}; solve {}",
+                record.day, record.part, parse, solve
+            )?;
+        }
+        writeln!(self.out)
+    }
+}
+
+struct Json<W> {
+    out: W,
+    rows: Vec<Row>,
+}
+
+impl<W: Write> Reporter for Json<W> {
+    fn record(&mut self, record: &Record) -> io::Result<()> {
+        self.rows.push(Row::from(record));
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        serde_json::to_writer_pretty(&mut self.out, &self.rows)?;
+        writeln!(self.out)
+    }
+}
+
+struct Csv<W> {
+    out: W,
+    memory: bool,
+    header: bool,
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+impl<W: Write> Reporter for Csv<W> {
+    fn record(&mut self, record: &Record) -> io::Result<()> {
+        if !self.header {
+            write!(
+                self.out,
+                "day,part,name,answer,status,error,parse_ms,solve_ms"
+            )?;
+            if self.memory {
+                write!(
+                    self.out,
+                    ",parse_allocs,parse_bytes,parse_peak,solve_allocs,solve_bytes,solve_peak"
+                )?;
+            }
+            writeln!(self.out)?;
+            self.header = true;
+        }
+
+        let row = Row::from(record);
+        write!(
+            self.out,
+            "{},{},{},{},{},{},{:.3},{:.3}",
+            row.day,
+            row.part,
+            csv_field(row.name),
+            csv_field(row.answer.as_deref().unwrap_or("")),
+            row.status,
+            csv_field(row.error.as_deref().unwrap_or("")),
+            row.parse_ms,
+            row.solve_ms
+        )?;
+        if self.memory {
+            for usage in [row.parse_memory, row.solve_memory] {
+                match usage {
+                    Some(usage) => write!(
+                        self.out,
+                        ",{},{},{}",
+                        usage.allocations, usage.bytes, usage.peak
+                    )?,
+                    None => write!(self.out, ",,,")?,
+                }
+            }
+        }
+        writeln!(self.out)
+    }
+}
+
+struct Markdown<W> {
+    out: W,
+    memory: bool,
+    header: bool,
+}
+
+fn markdown_cell(cell: &str) -> String {
+    cell.replace('|', "\\|").replace('\n', " ")
+}
+
+impl<W: Write> Reporter for Markdown<W> {
+    fn record(&mut self, record: &Record) -> io::Result<()> {
+        if !self.header {
+            if self.memory {
+                writeln!(
+                    self.out,
+                    "| Day | Part | Name | Answer | Status | Parse | Solve \
+                     | Parse allocs | Parse bytes | Parse peak \
+                     | Solve allocs | Solve bytes | Solve peak |"
+                )?;
+                writeln!(
+                    self.out,
+                    "|---:|---:|---|---:|---|---:|---:|---:|---:|---:|---:|---:|---:|"
+                )?;
+            } else {
+                writeln!(
+                    self.out,
+                    "| Day | Part | Name | Answer | Status | Parse | Solve |"
+                )?;
+                writeln!(self.out, "|---:|---:|---|---:|---|---:|---:|")?;
+            }
+            self.header = true;
+        }
+
+        let answer = match (record.answer(), record.error()) {
+            (Some(answer), _) => answer.to_string(),
+            (None, Some(err)) => err.to_string(),
+            (None, None) => String::new(),
+        };
+        write!(
+            self.out,
+            "| {} | {} | {} | {} | {} | {} | {} |",
+            record.day,
+            record.part,
+            markdown_cell(record.name),
+            markdown_cell(&answer),
+            record.status(),
+            format_duration(record.parse_time),
+            format_duration(record.solve_time)
+        )?;
+        if self.memory {
+            for usage in [record.parse_memory, record.solve_memory] {
+                match usage {
+                    Some(usage) => write!(
+                        self.out,
+                        " {} | {} | {} |",
+                        usage.allocations,
+                        format_bytes(usage.bytes),
+                        format_bytes(usage.peak)
+                    )?,
+                    None => write!(self.out, "  |  |  |")?,
+                }
+            }
+        }
+        writeln!(self.out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{error::DayError, solution::Part};
+
+    fn records() -> Vec<Record> {
+        vec![
+            Record {
+                parse_time: Duration::from_micros(1500),
+                solve_time: Duration::from_micros(250),
+                parse_memory: Some(Usage {
+                    allocations: 3,
+                    bytes: 2048,
+                    peak: 1024,
+                }),
+                solve_memory: Some(Usage::default()),
+                ..Record::solved(Part::One, 8)
+            },
+            Record {
+                parse_time: Duration::from_micros(1500),
+                ..Record::failed(Part::Two, DayError::Invalid("a, \"b\"".to_string()))
+            },
+        ]
+    }
+
+    fn render(format: Format, memory: bool) -> String {
+        let mut out = Vec::new();
+        {
+            let mut reporter = reporter(format, memory, &mut out);
+            for record in records() {
+                reporter.record(&record).unwrap();
+            }
+            reporter.finish().unwrap();
+        }
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_csv() {
+        assert_eq!(
+            "day,part,name,answer,status,error,parse_ms,solve_ms
+2,1,Cube Conundrum,8,ok,,1.500,0.250
+2,2,Cube Conundrum,,error,\"invalid input: a, \"\"b\"\"\",1.500,0.000
+",
+            render(Format::Csv, false)
+        );
+    }
+
+    #[test]
+    fn test_json() {
+        let res: serde_json::Value = serde_json::from_str(&render(Format::Json, true)).unwrap();
+        assert_eq!(2, res.as_array().unwrap().len());
+        assert_eq!("8", res[0]["answer"]);
+        assert_eq!("ok", res[0]["status"]);
+        assert_eq!(1.5, res[0]["parse_ms"]);
+        assert_eq!(3, res[0]["parse_memory"]["allocations"]);
+        assert_eq!(0, res[0]["solve_memory"]["peak"]);
+        assert_eq!(serde_json::Value::Null, res[1]["answer"]);
+        assert!(res[1].get("parse_memory").is_none());
+        assert_eq!("error", res[1]["status"]);
+    }
+
+    #[test]
+    fn test_text() {
+        let res = render(Format::Text, true);
+        assert!(res.contains(
+            "Result of day 2 part 1: 8 (parse 1.50ms, solve 250.0µs)
+Memory of day 2 part 1: parse 3 allocs, 2.00KiB, peak 1.00KiB; solve 0 allocs, 0B, peak 0B
+"
+        ));
+        assert!(res.contains("Day 2 part 2 failed\n\n"));
+    }
+
+    #[test]
+    fn test_markdown() {
+        assert_eq!(
+            "| Day | Part | Name | Answer | Status | Parse | Solve |
+|---:|---:|---|---:|---|---:|---:|
+| 2 | 1 | Cube Conundrum | 8 | ok | 1.50ms | 250.0µs |
+| 2 | 2 | Cube Conundrum | invalid input: a, \"b\" | error | 1.50ms | 0ns |
+",
+            render(Format::Markdown, false)
+        );
+    }
+
+    #[test]
+    fn test_csv_memory() {
+        assert_eq!(
+            "day,part,name,answer,status,error,parse_ms,solve_ms,\
+parse_allocs,parse_bytes,parse_peak,solve_allocs,solve_bytes,solve_peak
+2,1,Cube Conundrum,8,ok,,1.500,0.250,3,2048,1024,0,0,0
+2,2,Cube Conundrum,,error,\"invalid input: a, \"\"b\"\"\",1.500,0.000,,,,,,
+",
+            render(Format::Csv, true)
+        );
+    }
+
+    #[test]
+    fn test_markdown_memory() {
+        let res = render(Format::Markdown, true);
+        let lines: Vec<&str> = res.lines().collect();
+        assert_eq!(
+            "| Day | Part | Name | Answer | Status | Parse | Solve \
+| Parse allocs | Parse bytes | Parse peak | Solve allocs | Solve bytes | Solve peak |",
+            lines[0]
+        );
+        assert_eq!(
+            "| 2 | 1 | Cube Conundrum | 8 | ok | 1.50ms | 250.0µs \
+| 3 | 2.00KiB | 1.00KiB | 0 | 0B | 0B |",
+            lines[2]
+        );
+        assert!(lines[3].ends_with("| 0ns |  |  |  |  |  |  |"));
+    }
+}
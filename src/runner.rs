@@ -0,0 +1,466 @@
+use std::{
+    any::Any,
+    collections::BTreeMap,
+    panic::{self, AssertUnwindSafe},
+    sync::{mpsc, Arc},
+    time::Duration,
+};
+
+use rayon::{ThreadPoolBuildError, ThreadPoolBuilder};
+use tracing::{info, info_span};
+
+use crate::{
+    bench::timed,
+    cache::{self, Cache},
+    cancel::{self, Reason, Token},
+    error::DayError,
+    input::{self, Source},
+    memory::{self, Usage},
+    solution::{Answer, DynSolution, Part},
+};
+
+#[derive(Debug, Clone)]
+pub enum Outcome {
+    Solved(Answer),
+    /// Shared between the records of every part when parsing failed.
+    Failed(Arc<DayError>),
+}
+
+/// Result of running one part of one day.
+#[derive(Debug, Clone)]
+pub struct Record {
+    pub day: u32,
+    pub part: Part,
+    pub name: &'static str,
+    pub source: String,
+    pub outcome: Outcome,
+    pub parse_time: Duration,
+    pub solve_time: Duration,
+    /// Only measured when [`memory`] counting is enabled.
+    pub parse_memory: Option<Usage>,
+    pub solve_memory: Option<Usage>,
+    /// The answer came from the cache, nothing was run.
+    pub cached: bool,
+}
+
+impl Record {
+    pub fn status(&self) -> &'static str {
+        match self.cancelled() {
+            Some(reason) => reason.status(),
+            None if self.cached => "cached",
+            None if self.answer().is_some() => "ok",
+            None => "error",
+        }
+    }
+
+    pub fn answer(&self) -> Option<&Answer> {
+        match &self.outcome {
+            Outcome::Solved(answer) => Some(answer),
+            _ => None,
+        }
+    }
+
+    pub fn error(&self) -> Option<&DayError> {
+        match &self.outcome {
+            Outcome::Failed(err) => Some(err),
+            _ => None,
+        }
+    }
+
+    /// Why the part was stopped, if it was.
+    pub fn cancelled(&self) -> Option<Reason> {
+        match self.error() {
+            Some(DayError::Cancelled(reason)) => Some(*reason),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+impl Record {
+    /// Day 2 `part` solved with `answer` in no time, for tests.
+    pub(crate) fn solved(part: Part, answer: impl Into<Answer>) -> Self {
+        Self {
+            day: 2,
+            part,
+            name: "Cube Conundrum",
+            source: "inputs/day2".to_string(),
+            outcome: Outcome::Solved(answer.into()),
+            parse_time: Duration::ZERO,
+            solve_time: Duration::ZERO,
+            parse_memory: None,
+            solve_memory: None,
+            cached: false,
+        }
+    }
+
+    /// Day 2 `part` failed with `err`, for tests.
+    pub(crate) fn failed(part: Part, err: DayError) -> Self {
+        Self {
+            outcome: Outcome::Failed(Arc::new(err)),
+            ..Self::solved(part, 0)
+        }
+    }
+}
+
+/// How [`run_day`] and [`run_input`] run each part.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Options<'a> {
+    /// A part still running after this long fails with [`Reason::TimedOut`].
+    pub timeout: Option<Duration>,
+    /// Answers are looked up here before solving, and stored once solved.
+    pub cache: Option<&'a Cache>,
+}
+
+/// Loads the input from `source`, parses it once and solves each of `parts`.
+pub fn run_day(
+    solution: &dyn DynSolution,
+    source: &Source,
+    parts: &[Part],
+    options: &Options,
+) -> Vec<Record> {
+    match input::load(source) {
+        Ok(input) => run_input(solution, &source.to_string(), &input, parts, options),
+        Err(err) => failed(solution, &source.to_string(), parts, DayError::from(err)),
+    }
+}
+
+/// Parses `input` once and solves each of `parts`, `source` only names it.
+///
+/// Parts with a cached answer are not solved, and the input is not even
+/// parsed when every part has one. After Ctrl-C the remaining parts fail
+/// with [`Reason::Interrupted`] without running.
+pub fn run_input(
+    solution: &dyn DynSolution,
+    source: &str,
+    input: &str,
+    parts: &[Part],
+    options: &Options,
+) -> Vec<Record> {
+    let _day = info_span!("day", day = solution.day(), source).entered();
+    if cancel::interrupted() {
+        return failed(solution, source, parts, Reason::Interrupted.into());
+    }
+
+    let hash = options.cache.map(|_| cache::hash(input));
+    let cached = |part: Part| {
+        let answer = options
+            .cache?
+            .get(solution.day(), part, solution.version(), hash?)?;
+        let mut record = record(
+            solution,
+            source,
+            part,
+            Outcome::Solved(Answer::from(answer)),
+        );
+        record.cached = true;
+        info!(part = part.number(), "cached answer");
+        Some(record)
+    };
+    if let Some(records) = parts.iter().map(|&part| cached(part)).collect() {
+        return records;
+    }
+
+    let parse = info_span!("parse", bytes = input.len()).entered();
+    let ((parsed, parse_time), parse_memory) =
+        memory::measured(|| timed(|| caught(|| solution.parse(input))));
+    let parsed = match parsed {
+        Ok(parsed) => {
+            info!(?parse_time, "parsed");
+            parsed
+        }
+        Err(err) => {
+            info!(%err, "parse failed");
+            let mut records = failed(solution, source, parts, err);
+            for record in &mut records {
+                record.parse_time = parse_time;
+                record.parse_memory = parse_memory;
+            }
+            return records;
+        }
+    };
+    drop(parse);
+
+    parts
+        .iter()
+        .map(|&part| {
+            if let Some(record) = cached(part) {
+                return record;
+            }
+
+            let _solve = info_span!("solve", part = part.number()).entered();
+            let token = Token::new(options.timeout);
+            let ((res, solve_time), solve_memory) = memory::measured(|| {
+                timed(|| {
+                    token.check()?;
+                    cancel::scoped(token, || caught(|| solution.solve(part, parsed.as_ref())))
+                })
+            });
+            // Solutions that never check their token only stop once they are
+            // done, which is still too late.
+            let outcome = match (res, token.reason()) {
+                (Ok(_), Some(Reason::TimedOut)) => {
+                    Outcome::Failed(Arc::new(Reason::TimedOut.into()))
+                }
+                (Ok(answer), _) => {
+                    if let (Some(cache), Some(hash)) = (options.cache, hash) {
+                        let version = solution.version();
+                        cache.insert(solution.day(), part, version, hash, answer.to_string());
+                    }
+                    Outcome::Solved(answer)
+                }
+                (Err(err), _) => Outcome::Failed(Arc::new(err)),
+            };
+            match &outcome {
+                Outcome::Solved(answer) => info!(%answer, ?solve_time, "solved"),
+                Outcome::Failed(err) => info!(%err, ?solve_time, "failed"),
+            }
+            Record {
+                parse_time,
+                solve_time,
+                parse_memory,
+                solve_memory,
+                ..record(solution, source, part, outcome)
+            }
+        })
+        .collect()
+}
+
+/// Runs `f`, turning a panic into an error so that one bad input only fails
+/// its own day instead of the whole run.
+pub(crate) fn caught<T>(f: impl FnOnce() -> Result<T, DayError>) -> Result<T, DayError> {
+    panic::catch_unwind(AssertUnwindSafe(f))
+        .unwrap_or_else(|payload| Err(DayError::Panicked(panic_message(payload.as_ref()))))
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+fn record(solution: &dyn DynSolution, source: &str, part: Part, outcome: Outcome) -> Record {
+    Record {
+        day: solution.day(),
+        part,
+        name: solution.name(),
+        source: source.to_string(),
+        outcome,
+        parse_time: Duration::ZERO,
+        solve_time: Duration::ZERO,
+        parse_memory: None,
+        solve_memory: None,
+        cached: false,
+    }
+}
+
+/// One failed record per part, all sharing `err`.
+fn failed(solution: &dyn DynSolution, source: &str, parts: &[Part], err: DayError) -> Vec<Record> {
+    let err = Arc::new(err);
+    parts
+        .iter()
+        .map(|&part| record(solution, source, part, Outcome::Failed(err.clone())))
+        .collect()
+}
+
+/// Runs `task` for every item on a pool of `jobs` threads, `0` for one per
+/// CPU, and hands each result to `emit` in item order as soon as every
+/// earlier one has been emitted.
+pub fn run_ordered<I, T>(
+    items: &[I],
+    jobs: usize,
+    task: impl Fn(&I) -> T + Sync,
+    mut emit: impl FnMut(T),
+) -> Result<(), ThreadPoolBuildError>
+where
+    I: Sync,
+    T: Send,
+{
+    let pool = ThreadPoolBuilder::new().num_threads(jobs).build()?;
+    let (tx, rx) = mpsc::channel();
+    pool.in_place_scope(|scope| {
+        for (i, item) in items.iter().enumerate() {
+            let tx = tx.clone();
+            let task = &task;
+            scope.spawn(move |_| {
+                // The receiver outlives the scope, so this cannot fail.
+                let _ = tx.send((i, task(item)));
+            });
+        }
+        drop(tx);
+
+        let mut pending = BTreeMap::new();
+        let mut next = 0;
+        for (i, res) in rx {
+            pending.insert(i, res);
+            while let Some(res) = pending.remove(&next) {
+                emit(res);
+                next += 1;
+            }
+        }
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::{env, fs};
+
+    use super::*;
+    use crate::{advent23::day2::Day2, solution::Solution};
+
+    #[test]
+    fn test_run_day_parse_failure_fails_every_part() {
+        let path = env::temp_dir().join(format!("aoc-runner-{}", std::process::id()));
+        fs::write(&path, "Game 1: 3 purple\n").unwrap();
+
+        let records = run_day(
+            &Day2,
+            &Source::File(path.clone()),
+            &Part::ALL,
+            &Options::default(),
+        );
+        fs::remove_file(path).unwrap();
+
+        assert_eq!(2, records.len());
+        assert!(records.iter().all(|r| r.status() == "error"));
+        assert!(matches!(records[0].error(), Some(DayError::Located { .. })));
+    }
+
+    #[test]
+    fn test_run_input() {
+        let records = run_input(
+            &Day2,
+            "example",
+            "Game 1: 3 blue, 4 red, 1 green\n",
+            &[Part::Two],
+            &Options::default(),
+        );
+
+        assert_eq!(1, records.len());
+        assert_eq!(Part::Two, records[0].part);
+        assert_eq!("example", records[0].source);
+        assert_eq!(Some(&Answer::from(12)), records[0].answer());
+    }
+
+    /// Panics when parsing `parse` and in part 1.
+    pub(crate) struct Panics;
+
+    impl Solution for Panics {
+        type Parsed = ();
+
+        fn name(&self) -> &'static str {
+            "Panics"
+        }
+
+        fn day(&self) -> u32 {
+            25
+        }
+
+        fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+            match input {
+                "parse" => panic!("no parse"),
+                _ => Ok(()),
+            }
+        }
+
+        fn part1(&self, _: &Self::Parsed) -> Result<Answer, DayError> {
+            panic!("no part {}", 1)
+        }
+
+        fn part2(&self, _: &Self::Parsed) -> Result<Answer, DayError> {
+            Ok(Answer::from(2))
+        }
+    }
+
+    #[test]
+    fn test_run_input_panic() {
+        let records = run_input(&Panics, "example", "", &Part::ALL, &Options::default());
+        assert_eq!(
+            Some("the solution panicked: no part 1".to_string()),
+            records[0].error().map(DayError::to_string)
+        );
+        assert_eq!(Some(&Answer::from(2)), records[1].answer());
+
+        let records = run_input(&Panics, "example", "parse", &Part::ALL, &Options::default());
+        assert!(records.iter().all(
+            |r| matches!(r.error(), Some(DayError::Panicked(message)) if message == "no parse")
+        ));
+    }
+
+    #[test]
+    fn test_run_input_timeout() {
+        let records = run_input(
+            &Day2,
+            "example",
+            "Game 1: 3 blue, 4 red, 1 green\n",
+            &Part::ALL,
+            &Options {
+                timeout: Some(Duration::ZERO),
+                ..Options::default()
+            },
+        );
+
+        assert!(records.iter().all(|r| r.status() == "timeout"));
+        assert_eq!(Some(Reason::TimedOut), records[0].cancelled());
+    }
+
+    #[test]
+    fn test_run_input_cache() {
+        let cache = Cache::new(2023);
+        let options = Options {
+            cache: Some(&cache),
+            ..Options::default()
+        };
+        let input = "Game 1: 3 blue, 4 red, 1 green\n";
+
+        let records = run_input(&Day2, "example", input, &[Part::Two], &options);
+        assert_eq!("ok", records[0].status());
+
+        // Part 1 is solved on its own, part 2 comes from the cache.
+        let records = run_input(&Day2, "example", input, &Part::ALL, &options);
+        assert_eq!(vec!["ok", "cached"], statuses(&records));
+        assert_eq!(
+            Some("12".to_string()),
+            records[1].answer().map(|a| a.to_string())
+        );
+
+        // A broken input is not even parsed once everything is cached.
+        let (version, oops) = (Solution::version(&Day2), cache::hash("oops"));
+        cache.insert(2, Part::One, version, oops, "1".to_string());
+        cache.insert(2, Part::Two, version, oops, "2".to_string());
+        let records = run_input(&Day2, "example", "oops", &Part::ALL, &options);
+        assert_eq!(vec!["cached", "cached"], statuses(&records));
+        assert_eq!(
+            Some("12".to_string()),
+            cache.get(2, Part::Two, version, cache::hash(input))
+        );
+    }
+
+    fn statuses(records: &[Record]) -> Vec<&'static str> {
+        records.iter().map(Record::status).collect()
+    }
+
+    #[test]
+    fn test_run_ordered() {
+        let items: Vec<u64> = (0..8).collect();
+        let mut res = Vec::new();
+        run_ordered(
+            &items,
+            4,
+            |&i| {
+                // Later items finish first.
+                std::thread::sleep(Duration::from_millis(5 * (8 - i)));
+                i * 10
+            },
+            |n| res.push(n),
+        )
+        .unwrap();
+
+        assert_eq!(vec![0, 10, 20, 30, 40, 50, 60, 70], res);
+    }
+}
@@ -0,0 +1,306 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Where the day modules of `year` live, relative to the crate root.
+pub fn days_dir(year: u32) -> PathBuf {
+    Path::new("src").join(format!("advent{:02}", year % 100))
+}
+
+const TEMPLATE: &str = r#"use crate::{
+    error::DayError,
+    solution::{Answer, Example, Solution},
+};
+
+pub struct Day{day};
+
+const EXAMPLES: &[Example] = &[Example {
+    path: concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/{days_dir}/examples/day{day}.txt"
+    ),
+    input: include_str!("examples/day{day}.txt"),
+    part1: None,
+    part2: None,
+}];
+
+impl Solution for Day{day} {
+    type Parsed = Vec<String>;
+
+    fn name(&self) -> &'static str {
+        "{name}"
+    }
+
+    fn day(&self) -> u32 {
+        {day}
+    }
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        parse(input)
+    }
+
+    fn part1(&self, lines: &Self::Parsed) -> Result<Answer, DayError> {
+        Ok(part1(lines).into())
+    }
+
+    fn part2(&self, lines: &Self::Parsed) -> Result<Answer, DayError> {
+        Ok(part2(lines).into())
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        EXAMPLES
+    }
+}
+
+fn parse(input: &str) -> Result<Vec<String>, DayError> {
+    Ok(input
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect())
+}
+
+fn part1(_lines: &[String]) -> i64 {
+    0
+}
+
+fn part2(_lines: &[String]) -> i64 {
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "
+";
+
+    #[test]
+    fn test_part1() {
+        let res = part1(&parse(EXAMPLE).unwrap());
+        assert_eq!(0, res);
+    }
+
+    #[test]
+    fn test_part2() {
+        let res = part2(&parse(EXAMPLE).unwrap());
+        assert_eq!(0, res);
+    }
+}
+"#;
+
+/// The module of a new day, with a placeholder solution and tests.
+pub fn module(year: u32, day: u32, name: &str) -> String {
+    TEMPLATE
+        .replace("{days_dir}", &days_dir(year).to_string_lossy())
+        .replace("{day}", &day.to_string())
+        .replace("{name}", &name.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Adds `pub mod dayN;` and its registry entry to `mod_rs`, keeping both in
+/// day order.
+pub fn register(mod_rs: &str, day: u32) -> io::Result<String> {
+    let module = format!("pub mod day{};", day);
+    let entry = format!(
+        "    &Versioned::new(day{}::Day{}, include_str!(\"day{}.rs\")),",
+        day, day, day
+    );
+
+    let mut lines: Vec<&str> = mod_rs.lines().collect();
+    if lines.iter().any(|&line| line == module || line == entry) {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("day {} is already registered", day),
+        ));
+    }
+
+    // rustfmt orders modules by name, so `day10` goes before `day2`.
+    let name = format!("day{}", day);
+    insert_sorted(&mut lines, &module, name.as_str(), |line| {
+        line.strip_prefix("pub mod ")?.strip_suffix(';')
+    })?;
+    insert_sorted(&mut lines, &entry, day, |line| {
+        let (day, _) = line
+            .strip_prefix("    &Versioned::new(day")?
+            .split_once("::")?;
+        day.parse().ok()
+    })?;
+
+    let mut res = lines.join("\n");
+    res.push('\n');
+    Ok(res)
+}
+
+/// Inserts `line` before the first line with a greater key, or after the last
+/// line with a key.
+fn insert_sorted<'a, K: Ord>(
+    lines: &mut Vec<&'a str>,
+    line: &'a str,
+    key: K,
+    key_of: impl Fn(&'a str) -> Option<K>,
+) -> io::Result<()> {
+    let known: Vec<(usize, K)> = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(i, &l)| Some((i, key_of(l)?)))
+        .collect();
+    let at = match known.iter().find(|(_, k)| *k > key) {
+        Some(&(i, _)) => i,
+        None => match known.last() {
+            Some(&(i, _)) => i + 1,
+            None => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("no place found for `{}`", line.trim()),
+                ))
+            }
+        },
+    };
+    lines.insert(at, line);
+    Ok(())
+}
+
+/// Writes the module, its example and an empty input for `day` of `year`.
+///
+/// Nothing is written when the module, the example or the registration
+/// already exist; an existing input is kept as it is. Returns the files
+/// that were written.
+pub fn create(
+    root: &Path,
+    input_dir: &Path,
+    year: u32,
+    day: u32,
+    name: &str,
+) -> io::Result<Vec<PathBuf>> {
+    let days = root.join(days_dir(year));
+    let module_path = days.join(format!("day{}.rs", day));
+    let example_path = days.join("examples").join(format!("day{}.txt", day));
+    let mod_rs_path = days.join("mod.rs");
+
+    for path in [&module_path, &example_path] {
+        if path.exists() {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("{} already exists", path.display()),
+            ));
+        }
+    }
+    let mod_rs = match fs::read_to_string(&mod_rs_path) {
+        Ok(mod_rs) => register(&mod_rs, day)?,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!(
+                    "{} does not exist, add the module of {} first",
+                    mod_rs_path.display(),
+                    year
+                ),
+            ))
+        }
+        Err(err) => return Err(err),
+    };
+
+    fs::create_dir_all(example_path.parent().unwrap_or(&days))?;
+    create_new(&module_path, &module(year, day, name))?;
+    create_new(&example_path, "")?;
+    fs::write(&mod_rs_path, mod_rs)?;
+    let mut written = vec![module_path, example_path, mod_rs_path];
+
+    let input_path = input_dir.join(format!("day{}", day));
+    if !input_path.exists() {
+        fs::create_dir_all(input_dir)?;
+        create_new(&input_path, "")?;
+        written.push(input_path);
+    }
+
+    Ok(written)
+}
+
+fn create_new(path: &Path, content: &str) -> io::Result<()> {
+    use std::io::Write;
+
+    fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)?
+        .write_all(content.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    const MOD_RS: &str = r#"use crate::solution::{DynSolution, Versioned};
+
+pub mod day1;
+pub mod day5;
+
+pub static SOLUTIONS: &[&dyn DynSolution] = &[
+    &Versioned::new(day1::Day1, include_str!("day1.rs")),
+    &Versioned::new(day5::Day5, include_str!("day5.rs")),
+];
+"#;
+
+    #[test]
+    fn test_register() {
+        let res = register(MOD_RS, 3).unwrap();
+        assert_eq!(
+            r#"use crate::solution::{DynSolution, Versioned};
+
+pub mod day1;
+pub mod day3;
+pub mod day5;
+
+pub static SOLUTIONS: &[&dyn DynSolution] = &[
+    &Versioned::new(day1::Day1, include_str!("day1.rs")),
+    &Versioned::new(day3::Day3, include_str!("day3.rs")),
+    &Versioned::new(day5::Day5, include_str!("day5.rs")),
+];
+"#,
+            res
+        );
+
+        let res = register(MOD_RS, 6).unwrap();
+        assert!(res.contains("pub mod day5;\npub mod day6;\n"));
+        assert!(res.contains(
+            "day5.rs\")),\n    &Versioned::new(day6::Day6, include_str!(\"day6.rs\")),\n];"
+        ));
+
+        let res = register(MOD_RS, 12).unwrap();
+        assert!(res.contains("pub mod day1;\npub mod day12;\npub mod day5;\n"));
+        assert!(res.contains(
+            "day5.rs\")),\n    &Versioned::new(day12::Day12, include_str!(\"day12.rs\")),\n];"
+        ));
+
+        assert!(register(MOD_RS, 5).is_err());
+    }
+
+    #[test]
+    fn test_create() {
+        let root = env::temp_dir().join(format!("aoc-scaffold-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let days = root.join(days_dir(2023));
+        fs::create_dir_all(&days).unwrap();
+        fs::write(days.join("mod.rs"), MOD_RS).unwrap();
+        let inputs = root.join("inputs");
+
+        let res = create(&root, &inputs, 2023, 6, "Wait For It").unwrap();
+        assert_eq!(4, res.len());
+        let module = fs::read_to_string(days.join("day6.rs")).unwrap();
+        assert!(module.contains("\"/src/advent23/examples/day6.txt\""));
+        assert!(module.contains("pub struct Day6;"));
+        assert!(module.contains("\"Wait For It\""));
+        assert_eq!("", fs::read_to_string(inputs.join("day6")).unwrap());
+
+        let err = create(&root, &inputs, 2023, 6, "Wait For It").unwrap_err();
+        assert_eq!(io::ErrorKind::AlreadyExists, err.kind());
+
+        let err = create(&root, &inputs, 2024, 1, "Day 1").unwrap_err();
+        assert_eq!(io::ErrorKind::NotFound, err.kind());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
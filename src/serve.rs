@@ -0,0 +1,226 @@
+use std::{io::Read, thread, time::Duration};
+
+use serde::Serialize;
+use serde_json::json;
+use tiny_http::{Header, Method, Server};
+use tracing::warn;
+
+use crate::{
+    progress,
+    registry::Year,
+    report::Row,
+    runner::{self, Options},
+    solution::Part,
+};
+
+/// Source name of inputs posted to `/solve`, shown in error messages.
+const SOURCE: &str = "<request>";
+
+/// Largest accepted request body, far above any puzzle input.
+const MAX_BODY: u64 = 1 << 20;
+
+/// A JSON response to a request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+}
+
+impl Response {
+    fn json(status: u16, body: &impl Serialize) -> Self {
+        Self {
+            status,
+            body: serde_json::to_string(body).expect("responses serialize"),
+        }
+    }
+
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        Self::json(status, &json!({ "error": message.into() }))
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Day {
+    day: u32,
+    name: &'static str,
+    version: u32,
+}
+
+/// Answers one request:
+///
+/// - `GET /days` lists the registered solutions of `year`.
+/// - `POST /solve/{day}/{part}` solves a part for the input in `body` and
+///   returns its answer, status, error and timings like `run --format json`.
+pub fn handle(
+    year: &Year,
+    timeout: Option<Duration>,
+    method: &str,
+    path: &str,
+    body: &str,
+) -> Response {
+    let segments: Vec<&str> = path
+        .split('?')
+        .next()
+        .unwrap_or_default()
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    match (method, segments.as_slice()) {
+        ("GET", ["days"]) => {
+            let days: Vec<Day> = year
+                .solutions
+                .iter()
+                .map(|s| Day {
+                    day: s.day(),
+                    name: s.name(),
+                    version: s.version(),
+                })
+                .collect();
+            Response::json(200, &days)
+        }
+        ("POST", ["solve", day, part]) => {
+            let Some(solution) = day.parse().ok().and_then(|day| year.get(day)) else {
+                return Response::error(
+                    404,
+                    format!("no solution registered for {} day {}", year.year, day),
+                );
+            };
+            let part: Part = match part.parse() {
+                Ok(part) => part,
+                Err(err) => return Response::error(404, err),
+            };
+
+            let options = Options {
+                timeout,
+                ..Options::default()
+            };
+            let record = runner::run_input(solution, SOURCE, body, &[part], &options)
+                .pop()
+                .expect("one record per part");
+            let status = match record.status() {
+                "ok" => 200,
+                "timeout" => 504,
+                _ => 422,
+            };
+            Response::json(status, &Row::from(&record))
+        }
+        (_, ["days"] | ["solve", _, _]) => Response::error(405, "method not allowed"),
+        _ => Response::error(404, format!("no route for {}", path)),
+    }
+}
+
+/// Reads a request body of at most [`MAX_BODY`] bytes, or answers why not.
+fn read_body(reader: impl Read) -> Result<String, Response> {
+    let mut body = String::new();
+    reader
+        .take(MAX_BODY + 1)
+        .read_to_string(&mut body)
+        .map_err(|err| Response::error(400, format!("unreadable body: {}", err)))?;
+    if body.len() as u64 > MAX_BODY {
+        return Err(Response::error(
+            413,
+            format!("body larger than {} bytes", MAX_BODY),
+        ));
+    }
+    Ok(body)
+}
+
+/// Serves [`handle`] on `127.0.0.1:port`, one thread per request, until the
+/// process stops.
+pub fn serve(year: &'static Year, port: u16, timeout: Option<Duration>) -> Result<(), String> {
+    // Requests run side by side, their bars would only garble the log.
+    progress::hide();
+    let server = Server::http(("127.0.0.1", port)).map_err(|err| err.to_string())?;
+    let content_type =
+        Header::from_bytes("Content-Type", "application/json").expect("valid header");
+
+    for mut request in server.incoming_requests() {
+        let content_type = content_type.clone();
+        thread::spawn(move || {
+            let response = match read_body(request.as_reader()) {
+                Ok(body) => {
+                    let method = match request.method() {
+                        Method::Get => "GET",
+                        Method::Post => "POST",
+                        _ => "",
+                    };
+                    handle(year, timeout, method, request.url(), &body)
+                }
+                Err(response) => response,
+            };
+            let reply = tiny_http::Response::from_string(response.body)
+                .with_status_code(response.status)
+                .with_header(content_type);
+            if let Err(err) = request.respond(reply) {
+                warn!(%err, "failed to respond");
+            }
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry;
+
+    fn handle(method: &str, path: &str, body: &str) -> Response {
+        super::handle(registry::get(2023).unwrap(), None, method, path, body)
+    }
+
+    #[test]
+    fn test_days() {
+        let res = handle("GET", "/days", "");
+        assert_eq!(200, res.status);
+        let body: serde_json::Value = serde_json::from_str(&res.body).unwrap();
+        assert_eq!(1, body[0]["day"]);
+        assert_eq!("Trebuchet?!", body[0]["name"]);
+        assert!(body[0]["version"].is_u64());
+    }
+
+    #[test]
+    fn test_solve() {
+        let res = handle("POST", "/solve/1/1", "1abc2\npqr3stu8vwx\n");
+        assert_eq!(200, res.status);
+        let body: serde_json::Value = serde_json::from_str(&res.body).unwrap();
+        assert_eq!("50", body["answer"]);
+        assert_eq!("ok", body["status"]);
+        assert!(body["error"].is_null());
+        assert!(body["solve_ms"].is_number());
+    }
+
+    #[test]
+    fn test_solve_parse_error() {
+        let res = handle("POST", "/solve/2/1", "Game one: 3 blue\n");
+        assert_eq!(422, res.status);
+        let body: serde_json::Value = serde_json::from_str(&res.body).unwrap();
+        assert_eq!("error", body["status"]);
+        assert!(body["answer"].is_null());
+        assert_eq!(
+            "1:6: bad number `one`: invalid digit found in string",
+            body["error"]
+        );
+    }
+
+    #[test]
+    fn test_routes() {
+        assert_eq!(404, handle("POST", "/solve/25/1", "").status);
+        assert_eq!(404, handle("POST", "/solve/1/3", "").status);
+        assert_eq!(404, handle("GET", "/nope", "").status);
+        assert_eq!(405, handle("GET", "/solve/1/1", "").status);
+        assert_eq!(
+            r#"{"error":"invalid part `3`, expected 1 or 2"}"#,
+            handle("POST", "/solve/1/3", "").body
+        );
+    }
+
+    #[test]
+    fn test_read_body() {
+        assert_eq!("1abc2\n", read_body("1abc2\n".as_bytes()).unwrap());
+
+        let large = "1".repeat(MAX_BODY as usize + 1);
+        assert_eq!(413, read_body(large.as_bytes()).unwrap_err().status);
+        assert_eq!(400, read_body(&[0xff][..]).unwrap_err().status);
+    }
+}
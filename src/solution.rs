@@ -0,0 +1,342 @@
+use std::{any::Any, fmt, str::FromStr};
+
+use crate::{cache, error::DayError};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Answer {
+    I64(i64),
+    U64(u64),
+    Str(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::I64(n) => write!(f, "{}", n),
+            Answer::U64(n) => write!(f, "{}", n),
+            Answer::Str(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl From<i32> for Answer {
+    fn from(value: i32) -> Self {
+        Answer::I64(value.into())
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(value: i64) -> Self {
+        Answer::I64(value)
+    }
+}
+
+impl From<u32> for Answer {
+    fn from(value: u32) -> Self {
+        Answer::U64(value.into())
+    }
+}
+
+impl From<u64> for Answer {
+    fn from(value: u64) -> Self {
+        Answer::U64(value)
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(value: usize) -> Self {
+        Answer::U64(value as u64)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Str(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Answer::Str(value.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+
+    pub fn number(self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.number())
+    }
+}
+
+impl FromStr for Part {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            part => Err(format!("invalid part `{}`, expected 1 or 2", part)),
+        }
+    }
+}
+
+/// One of the worked examples from the puzzle text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Example {
+    /// Where the example lives on disk, so it can be edited and re-read.
+    pub path: &'static str,
+    /// The example as it was compiled in.
+    pub input: &'static str,
+    pub part1: Option<&'static str>,
+    pub part2: Option<&'static str>,
+}
+
+impl Example {
+    pub fn name(&self) -> &'static str {
+        self.path.rsplit('/').next().unwrap_or(self.path)
+    }
+
+    pub fn expected(&self, part: Part) -> Option<&'static str> {
+        match part {
+            Part::One => self.part1,
+            Part::Two => self.part2,
+        }
+    }
+}
+
+/// A day specific command of the REPL, such as `trace <seed>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Query {
+    pub name: &'static str,
+    pub usage: &'static str,
+    pub help: &'static str,
+}
+
+impl Query {
+    /// Argument `i` of the query, or an error showing its usage.
+    pub fn arg<'a>(&self, args: &[&'a str], i: usize) -> Result<&'a str, DayError> {
+        args.get(i)
+            .copied()
+            .ok_or_else(|| DayError::Query(format!("usage: {}", self.usage)))
+    }
+}
+
+/// A single day of the calendar.
+///
+/// Parsing is kept separate from solving so both parts can share one parsed
+/// model and so the two steps can be measured on their own.
+pub trait Solution {
+    type Parsed: 'static;
+
+    fn name(&self) -> &'static str;
+
+    fn day(&self) -> u32;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError>;
+
+    fn part1(&self, parsed: &Self::Parsed) -> Result<Answer, DayError>;
+
+    fn part2(&self, parsed: &Self::Parsed) -> Result<Answer, DayError>;
+
+    fn examples(&self) -> &'static [Example] {
+        &[]
+    }
+
+    /// Bumped whenever a change outside the day's own file could alter the
+    /// answers, so cached answers of earlier versions are not reused. Edits
+    /// to the file itself are caught by [`Versioned`].
+    fn version(&self) -> u32 {
+        1
+    }
+
+    /// Queries the REPL offers on top of its generic commands.
+    fn queries(&self) -> &'static [Query] {
+        &[]
+    }
+
+    /// Runs `query`, one of [`Solution::queries`], against the parsed input.
+    fn query(
+        &self,
+        _parsed: &Self::Parsed,
+        query: &Query,
+        _args: &[&str],
+    ) -> Result<String, DayError> {
+        Err(DayError::Query(format!("unknown query `{}`", query.name)))
+    }
+}
+
+/// Object safe view of a [`Solution`], used by the registry so days with
+/// different parsed models can live in one list.
+pub trait DynSolution: Sync {
+    fn name(&self) -> &'static str;
+
+    fn day(&self) -> u32;
+
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>, DayError>;
+
+    fn solve(&self, part: Part, parsed: &dyn Any) -> Result<Answer, DayError>;
+
+    fn examples(&self) -> &'static [Example];
+
+    fn version(&self) -> u32;
+
+    fn queries(&self) -> &'static [Query];
+
+    fn query(&self, parsed: &dyn Any, query: &Query, args: &[&str]) -> Result<String, DayError>;
+}
+
+impl<S> DynSolution for S
+where
+    S: Solution + Sync,
+{
+    fn name(&self) -> &'static str {
+        Solution::name(self)
+    }
+
+    fn day(&self) -> u32 {
+        Solution::day(self)
+    }
+
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>, DayError> {
+        let parsed = Solution::parse(self, input).map_err(|err| err.locate(input))?;
+        Ok(Box::new(parsed))
+    }
+
+    fn solve(&self, part: Part, parsed: &dyn Any) -> Result<Answer, DayError> {
+        let parsed = downcast::<S>(self, parsed);
+        match part {
+            Part::One => self.part1(parsed),
+            Part::Two => self.part2(parsed),
+        }
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        Solution::examples(self)
+    }
+
+    fn version(&self) -> u32 {
+        Solution::version(self)
+    }
+
+    fn queries(&self) -> &'static [Query] {
+        Solution::queries(self)
+    }
+
+    fn query(&self, parsed: &dyn Any, query: &Query, args: &[&str]) -> Result<String, DayError> {
+        Solution::query(self, downcast::<S>(self, parsed), query, args)
+    }
+}
+
+/// A registered [`Solution`] whose version also covers a hash of its source
+/// file, so editing a day invalidates its cached answers.
+pub struct Versioned<S> {
+    solution: S,
+    source: &'static str,
+}
+
+impl<S> Versioned<S> {
+    /// `source` is the day's file, `include_str!("dayN.rs")`.
+    pub const fn new(solution: S, source: &'static str) -> Self {
+        Self { solution, source }
+    }
+}
+
+impl<S> DynSolution for Versioned<S>
+where
+    S: Solution + Sync,
+{
+    fn name(&self) -> &'static str {
+        DynSolution::name(&self.solution)
+    }
+
+    fn day(&self) -> u32 {
+        DynSolution::day(&self.solution)
+    }
+
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>, DayError> {
+        DynSolution::parse(&self.solution, input)
+    }
+
+    fn solve(&self, part: Part, parsed: &dyn Any) -> Result<Answer, DayError> {
+        self.solution.solve(part, parsed)
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        DynSolution::examples(&self.solution)
+    }
+
+    fn version(&self) -> u32 {
+        let hash = cache::hash(self.source) ^ u64::from(Solution::version(&self.solution));
+        (hash ^ (hash >> 32)) as u32
+    }
+
+    fn queries(&self) -> &'static [Query] {
+        DynSolution::queries(&self.solution)
+    }
+
+    fn query(&self, parsed: &dyn Any, query: &Query, args: &[&str]) -> Result<String, DayError> {
+        DynSolution::query(&self.solution, parsed, query, args)
+    }
+}
+
+fn downcast<'a, S: Solution>(solution: &S, parsed: &'a dyn Any) -> &'a S::Parsed {
+    match parsed.downcast_ref::<S::Parsed>() {
+        Some(parsed) => parsed,
+        None => panic!(
+            "Parsed input does not belong to day {}",
+            Solution::day(solution)
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_answer_display() {
+        assert_eq!("42", Answer::from(42).to_string());
+        assert_eq!("-7", Answer::from(-7i64).to_string());
+        assert_eq!("18446744073709551615", Answer::from(u64::MAX).to_string());
+        assert_eq!("abc", Answer::from("abc").to_string());
+    }
+
+    #[test]
+    fn test_versioned() {
+        use crate::advent23::day2::Day2;
+
+        let (v1, v2) = (
+            Versioned::new(Day2, "fn part1() {}"),
+            Versioned::new(Day2, "fn part1() { 1 }"),
+        );
+        assert_ne!(v1.version(), v2.version());
+        assert_eq!(
+            v1.version(),
+            Versioned::new(Day2, "fn part1() {}").version()
+        );
+        assert_eq!("Cube Conundrum", v1.name());
+    }
+
+    #[test]
+    fn test_part_from_str() {
+        assert_eq!(Ok(Part::One), "1".parse());
+        assert_eq!(Ok(Part::Two), "2".parse());
+        assert!("3".parse::<Part>().is_err());
+    }
+}
@@ -0,0 +1,389 @@
+use std::{
+    fmt, fs, io,
+    path::Path,
+    time::{Duration, SystemTime},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    fetch::{Client, FetchError},
+    solution::Part,
+};
+
+pub const DEFAULT_HISTORY_FILE: &str = "submissions.json";
+
+/// What the server said about a submitted answer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "outcome", content = "seconds")]
+pub enum Outcome {
+    Correct,
+    TooHigh,
+    TooLow,
+    Wrong,
+    /// Submitted too soon after the previous answer.
+    Wait(u64),
+    /// The part was already solved, or is not unlocked.
+    WrongLevel,
+    Unknown,
+}
+
+impl Outcome {
+    /// Parses the article of the response page.
+    pub fn parse(page: &str) -> Self {
+        if page.contains("That's the right answer") {
+            Outcome::Correct
+        } else if page.contains("You gave an answer too recently") {
+            Outcome::Wait(wait_seconds(page).unwrap_or(60))
+        } else if page.contains("your answer is too high") {
+            Outcome::TooHigh
+        } else if page.contains("your answer is too low") {
+            Outcome::TooLow
+        } else if page.contains("That's not the right answer") {
+            Outcome::Wrong
+        } else if page.contains("You don't seem to be solving the right level") {
+            Outcome::WrongLevel
+        } else {
+            Outcome::Unknown
+        }
+    }
+
+    pub fn is_wrong(&self) -> bool {
+        matches!(self, Outcome::TooHigh | Outcome::TooLow | Outcome::Wrong)
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Correct => write!(f, "correct"),
+            Outcome::TooHigh => write!(f, "too high"),
+            Outcome::TooLow => write!(f, "too low"),
+            Outcome::Wrong => write!(f, "wrong"),
+            Outcome::Wait(seconds) => write!(f, "wait {} seconds", seconds),
+            Outcome::WrongLevel => write!(f, "already solved or not unlocked"),
+            Outcome::Unknown => write!(f, "unrecognized response"),
+        }
+    }
+}
+
+/// Reads `You have 1m 23s left to wait` as 83. The page says "you have to
+/// wait" before that, so only the last "have" counts.
+fn wait_seconds(page: &str) -> Option<u64> {
+    let page = page.to_ascii_lowercase();
+    let (before, _) = page.split_once(" left to wait")?;
+    let (_, left) = before.rsplit_once("have ")?;
+    left.split_whitespace().try_fold(0, |total, unit| {
+        let (n, scale) = match unit.strip_suffix('m') {
+            Some(n) => (n, 60),
+            None => (unit.strip_suffix('s')?, 1),
+        };
+        Some(total + n.parse::<u64>().ok()? * scale)
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Submission {
+    pub year: u32,
+    pub day: u32,
+    pub part: u8,
+    pub answer: String,
+    #[serde(flatten)]
+    pub outcome: Outcome,
+    /// Seconds since the Unix epoch.
+    pub at: u64,
+}
+
+/// Why an answer is not sent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Refusal {
+    AlreadySolved(String),
+    KnownWrong(Outcome),
+    /// Not below an answer known to be too high, or the reverse.
+    OutOfBounds(Outcome, String),
+    Throttled(Duration),
+}
+
+impl fmt::Display for Refusal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Refusal::AlreadySolved(answer) => write!(f, "already solved with {}", answer),
+            Refusal::KnownWrong(outcome) => {
+                write!(f, "this answer was already submitted, it is {}", outcome)
+            }
+            Refusal::OutOfBounds(outcome, answer) => {
+                write!(f, "{} was already {}, so this one is too", answer, outcome)
+            }
+            Refusal::Throttled(remaining) => write!(
+                f,
+                "the server asked to wait, {} seconds left",
+                remaining.as_secs()
+            ),
+        }
+    }
+}
+
+/// Every answer submitted so far, kept so nothing known to be wrong is sent
+/// twice.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct History {
+    submissions: Vec<Submission>,
+}
+
+impl History {
+    /// Reads `path`, an absent file is an empty history.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut content = serde_json::to_string_pretty(self)?;
+        content.push('\n');
+        fs::write(path, content)
+    }
+
+    pub fn push(&mut self, submission: Submission) {
+        self.submissions.push(submission);
+    }
+
+    /// Checks `answer` against earlier submissions at `now`, in seconds
+    /// since the Unix epoch.
+    pub fn check(
+        &self,
+        year: u32,
+        day: u32,
+        part: Part,
+        answer: &str,
+        now: u64,
+    ) -> Result<(), Refusal> {
+        let earlier = || {
+            self.submissions
+                .iter()
+                .filter(move |s| s.year == year && s.day == day && s.part == part.number())
+        };
+
+        if let Some(s) = earlier().find(|s| s.outcome == Outcome::Correct) {
+            return Err(Refusal::AlreadySolved(s.answer.clone()));
+        }
+        if let Some(s) = earlier().find(|s| s.answer == answer && s.outcome.is_wrong()) {
+            return Err(Refusal::KnownWrong(s.outcome.clone()));
+        }
+
+        if let Ok(value) = answer.parse::<i128>() {
+            for s in earlier() {
+                let Ok(known) = s.answer.parse::<i128>() else {
+                    continue;
+                };
+                let out_of_bounds = match s.outcome {
+                    Outcome::TooHigh => value >= known,
+                    Outcome::TooLow => value <= known,
+                    _ => false,
+                };
+                if out_of_bounds {
+                    return Err(Refusal::OutOfBounds(s.outcome.clone(), s.answer.clone()));
+                }
+            }
+        }
+
+        // Waits apply to the whole account, not just this part.
+        let until = self
+            .submissions
+            .iter()
+            .filter_map(|s| match s.outcome {
+                Outcome::Wait(seconds) => Some(s.at + seconds),
+                _ => None,
+            })
+            .max();
+        match until {
+            Some(until) if until > now => Err(Refusal::Throttled(Duration::from_secs(until - now))),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Seconds since the Unix epoch.
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+#[derive(Debug)]
+pub enum SubmitError {
+    Refused(Refusal),
+    Fetch(FetchError),
+}
+
+impl fmt::Display for SubmitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SubmitError::Refused(refusal) => write!(f, "{}", refusal),
+            SubmitError::Fetch(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for SubmitError {}
+
+/// Posts `answer` unless `history` refuses it, and adds the outcome to
+/// `history`. `client` is only made once the answer is accepted, so a
+/// refusal needs no session.
+pub fn submit(
+    history: &mut History,
+    client: impl FnOnce() -> Result<Client, FetchError>,
+    year: u32,
+    day: u32,
+    part: Part,
+    answer: &str,
+) -> Result<Outcome, SubmitError> {
+    history
+        .check(year, day, part, answer, now())
+        .map_err(SubmitError::Refused)?;
+    let page = client()
+        .and_then(|client| client.answer(year, day, part.number(), answer))
+        .map_err(SubmitError::Fetch)?;
+
+    let outcome = Outcome::parse(&page);
+    history.push(Submission {
+        year,
+        day,
+        part: part.number(),
+        answer: answer.to_string(),
+        outcome: outcome.clone(),
+        at: now(),
+    });
+    Ok(outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn submission(answer: &str, outcome: Outcome, at: u64) -> Submission {
+        Submission {
+            year: 2023,
+            day: 4,
+            part: 1,
+            answer: answer.to_string(),
+            outcome,
+            at,
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            Outcome::Correct,
+            Outcome::parse("<article><p>That's the right answer! You are one gold star closer")
+        );
+        assert_eq!(
+            Outcome::TooHigh,
+            Outcome::parse("That's not the right answer; your answer is too high.")
+        );
+        assert_eq!(
+            Outcome::TooLow,
+            Outcome::parse("That's not the right answer; your answer is too low.")
+        );
+        assert_eq!(
+            Outcome::Wrong,
+            Outcome::parse("That's not the right answer. If you're stuck")
+        );
+        assert_eq!(
+            Outcome::Wait(34),
+            Outcome::parse(
+                "<article><p>You gave an answer too recently; you have to wait after \
+                 submitting an answer before trying again.  You have 34s left to wait. \
+                 [<a href=\"/2023/day/4\">Return to Day 4</a>]</p></article>"
+            )
+        );
+        assert_eq!(
+            Outcome::Wait(83),
+            Outcome::parse(
+                "<article><p>You gave an answer too recently; you have to wait after \
+                 submitting an answer before trying again.  You have 1m 23s left to wait. \
+                 [<a href=\"/2023/day/4\">Return to Day 4</a>]</p></article>"
+            )
+        );
+        assert_eq!(
+            Outcome::WrongLevel,
+            Outcome::parse("You don't seem to be solving the right level.")
+        );
+        assert_eq!(Outcome::Unknown, Outcome::parse("<html></html>"));
+    }
+
+    #[test]
+    fn test_check() {
+        let mut history = History::default();
+        history.push(submission("100", Outcome::TooHigh, 0));
+        history.push(submission("10", Outcome::TooLow, 0));
+        history.push(submission("42", Outcome::Wrong, 0));
+
+        assert_eq!(Ok(()), history.check(2023, 4, Part::One, "50", 0));
+        assert_eq!(Ok(()), history.check(2023, 4, Part::Two, "100", 0));
+        assert_eq!(
+            Err(Refusal::KnownWrong(Outcome::Wrong)),
+            history.check(2023, 4, Part::One, "42", 0)
+        );
+        assert_eq!(
+            Err(Refusal::OutOfBounds(Outcome::TooHigh, "100".to_string())),
+            history.check(2023, 4, Part::One, "150", 0)
+        );
+        assert_eq!(
+            Err(Refusal::OutOfBounds(Outcome::TooLow, "10".to_string())),
+            history.check(2023, 4, Part::One, "3", 0)
+        );
+
+        history.push(submission("50", Outcome::Wait(30), 1000));
+        assert_eq!(
+            Err(Refusal::Throttled(Duration::from_secs(20))),
+            history.check(2023, 4, Part::Two, "1", 1010)
+        );
+        assert_eq!(Ok(()), history.check(2023, 4, Part::One, "50", 1030));
+
+        history.push(submission("55", Outcome::Correct, 1040));
+        assert_eq!(
+            Err(Refusal::AlreadySolved("55".to_string())),
+            history.check(2023, 4, Part::One, "55", 1050)
+        );
+    }
+
+    #[test]
+    fn test_history_round_trip() {
+        let mut history = History::default();
+        history.push(submission("100", Outcome::TooHigh, 1));
+        history.push(submission("50", Outcome::Wait(30), 2));
+
+        let json = serde_json::to_string(&history).unwrap();
+        assert!(json.contains(r#""outcome":"too_high""#));
+        assert!(json.contains(r#""outcome":"wait","seconds":30"#));
+        assert_eq!(history, serde_json::from_str(&json).unwrap());
+    }
+
+    #[test]
+    fn test_submit() {
+        let mut history = History::default();
+        history.push(submission("13", Outcome::Correct, 0));
+        let res = submit(&mut history, || panic!("refused"), 2023, 4, Part::One, "14");
+        assert!(matches!(
+            res,
+            Err(SubmitError::Refused(Refusal::AlreadySolved(_)))
+        ));
+
+        let mut history = History::default();
+        let res = submit(
+            &mut history,
+            || Err(FetchError::MissingSession(None)),
+            2023,
+            4,
+            Part::One,
+            "14",
+        );
+        assert!(matches!(res, Err(SubmitError::Fetch(_))));
+        assert_eq!(History::default(), history);
+    }
+}
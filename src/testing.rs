@@ -0,0 +1,12 @@
+//! Helpers shared by the unit tests.
+
+use std::{env, fs, path::PathBuf, process};
+
+/// An empty directory named after `name` and this process, so parallel tests
+/// and test runs do not share it.
+pub(crate) fn temp_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("aoc-{}-{}", name, process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
@@ -0,0 +1,123 @@
+use std::{
+    io::{self, Write},
+    path::Path,
+    sync::mpsc,
+    thread,
+    time::Duration,
+};
+
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    style::{Attribute, Print, SetAttribute},
+    terminal::{self, ClearType},
+    ExecutableCommand, QueueableCommand,
+};
+
+use adventofcode23::{
+    answers::Answers,
+    dashboard::Dashboard,
+    input, progress,
+    registry::Year,
+    runner::{self, Options, Record},
+};
+
+/// How often the screen is redrawn while nothing happens, to move the
+/// progress bar and the running time along.
+const TICK: Duration = Duration::from_millis(100);
+
+/// Raw mode and the alternate screen, left again when dropped, also on errors.
+struct Screen;
+
+impl Screen {
+    fn enter() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        let screen = Self;
+        io::stdout()
+            .execute(terminal::EnterAlternateScreen)?
+            .execute(cursor::Hide)?;
+        Ok(screen)
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        let _ = io::stdout()
+            .execute(cursor::Show)
+            .and_then(|out| out.execute(terminal::LeaveAlternateScreen));
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Shows the dashboard until `q`; parts run on a worker thread, one at a time.
+pub fn run(year: &Year, answers: &Answers, input: Option<&Path>) -> io::Result<()> {
+    progress::hide();
+    let mut dashboard = Dashboard::new(year, answers);
+    let (tx, rx) = mpsc::channel::<Record>();
+
+    let _screen = Screen::enter()?;
+    loop {
+        if let Ok(record) = rx.try_recv() {
+            dashboard.finish(&record);
+        }
+        if dashboard.is_running() {
+            dashboard.set_progress(progress::current());
+        }
+        draw(&dashboard)?;
+
+        if !event::poll(TICK)? {
+            continue;
+        }
+        let Event::Key(KeyEvent {
+            code,
+            modifiers,
+            kind: KeyEventKind::Press,
+            ..
+        }) = event::read()?
+        else {
+            continue;
+        };
+        match code {
+            KeyCode::Up | KeyCode::Char('k') => dashboard.select_previous(),
+            KeyCode::Down | KeyCode::Char('j') => dashboard.select_next(),
+            KeyCode::Enter | KeyCode::Char('r') => {
+                if let Some((solution, part)) = dashboard.start() {
+                    let source = input::resolve(year.year, solution.day(), input);
+                    let tx = tx.clone();
+                    progress::reset();
+                    thread::spawn(move || {
+                        let records =
+                            runner::run_day(solution, &source, &[part], &Options::default());
+                        for record in records {
+                            let _ = tx.send(record);
+                        }
+                    });
+                }
+            }
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            // Raw mode turns Ctrl-C into a key instead of a signal.
+            KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => return Ok(()),
+            _ => {}
+        }
+    }
+}
+
+fn draw(dashboard: &Dashboard) -> io::Result<()> {
+    let (width, height) = terminal::size()?;
+    let (lines, selected) = dashboard.view(width as usize, height as usize);
+
+    let mut out = io::stdout().lock();
+    for (i, line) in lines.iter().enumerate() {
+        out.queue(cursor::MoveTo(0, i as u16))?
+            .queue(terminal::Clear(ClearType::CurrentLine))?;
+        if i == selected {
+            out.queue(SetAttribute(Attribute::Reverse))?
+                .queue(Print(format!("{:<1$}", line, width as usize)))?
+                .queue(SetAttribute(Attribute::Reset))?;
+        } else {
+            out.queue(Print(line))?;
+        }
+    }
+    out.queue(terminal::Clear(ClearType::FromCursorDown))?;
+    out.flush()
+}
@@ -0,0 +1,202 @@
+use std::{fmt, time::Duration};
+
+use crate::{
+    answers::Answers,
+    runner::{self, Options, Record},
+    solution::{DynSolution, Example, Part},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: String, actual: String },
+    Missing,
+    Error,
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            Verdict::Pass => "PASS",
+            Verdict::Fail { .. } => "FAIL",
+            Verdict::Missing => "MISSING",
+            Verdict::Error => "ERROR",
+        };
+        f.pad(label)
+    }
+}
+
+/// Number of parts per verdict.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Tally {
+    pub passed: usize,
+    pub failed: usize,
+    pub missing: usize,
+    pub errors: usize,
+}
+
+impl Tally {
+    pub fn add(&mut self, verdict: &Verdict) {
+        match verdict {
+            Verdict::Pass => self.passed += 1,
+            Verdict::Fail { .. } => self.failed += 1,
+            Verdict::Missing => self.missing += 1,
+            Verdict::Error => self.errors += 1,
+        }
+    }
+
+    /// Whether the run failed. A recorded run accepts the new answers, so
+    /// only errors fail it.
+    pub fn is_failure(&self, recorded: bool) -> bool {
+        self.errors > 0 || (self.failed > 0 && !recorded)
+    }
+}
+
+impl fmt::Display for Tally {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} passed, {} failed, {} missing, {} errors",
+            self.passed, self.failed, self.missing, self.errors
+        )
+    }
+}
+
+/// Compares the answer in `record` with the accepted one for `year` in
+/// `answers`.
+pub fn check(answers: &Answers, year: u32, record: &Record) -> Verdict {
+    compare(answers.get(year, record.day, record.part), record)
+}
+
+/// Compares the answer in `record` with `expected`.
+pub fn compare(expected: Option<&str>, record: &Record) -> Verdict {
+    let actual = match record.answer() {
+        Some(answer) => answer.to_string(),
+        None => return Verdict::Error,
+    };
+
+    match expected {
+        Some(expected) if expected == actual => Verdict::Pass,
+        Some(expected) => Verdict::Fail {
+            expected: expected.to_string(),
+            actual,
+        },
+        None => Verdict::Missing,
+    }
+}
+
+/// The run of one registered example.
+#[derive(Debug)]
+pub struct ExampleRun {
+    pub example: &'static Example,
+    /// One per part with a known answer.
+    pub records: Vec<Record>,
+    /// The verdict of each record.
+    pub verdicts: Vec<Verdict>,
+}
+
+/// Runs the registered examples of `solution`, only the parts of `parts`
+/// with a known answer.
+pub fn examples(
+    solution: &dyn DynSolution,
+    parts: &[Part],
+    timeout: Option<Duration>,
+) -> Vec<ExampleRun> {
+    let options = Options {
+        timeout,
+        ..Options::default()
+    };
+    solution
+        .examples()
+        .iter()
+        .map(|example| {
+            let parts: Vec<Part> = parts
+                .iter()
+                .copied()
+                .filter(|&part| example.expected(part).is_some())
+                .collect();
+            let records =
+                runner::run_input(solution, example.path, example.input, &parts, &options);
+            let verdicts = records
+                .iter()
+                .map(|record| compare(example.expected(record.part), record))
+                .collect();
+            ExampleRun {
+                example,
+                records,
+                verdicts,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{advent23::day1::Day1, error::DayError};
+
+    #[test]
+    fn test_check() {
+        let answers = Answers::parse("[2023.day2]\npart1 = \"2771\"").unwrap();
+
+        let res = check(&answers, 2023, &Record::solved(Part::One, 2771));
+        assert_eq!(Verdict::Pass, res);
+
+        let res = check(&answers, 2023, &Record::solved(Part::One, 2772));
+        assert_eq!(
+            Verdict::Fail {
+                expected: "2771".to_string(),
+                actual: "2772".to_string()
+            },
+            res
+        );
+
+        let res = check(&answers, 2023, &Record::solved(Part::Two, 1));
+        assert_eq!(Verdict::Missing, res);
+
+        let err = DayError::Invalid("broken".to_string());
+        let res = check(&answers, 2023, &Record::failed(Part::One, err));
+        assert_eq!(Verdict::Error, res);
+    }
+
+    #[test]
+    fn test_tally() {
+        let mut tally = Tally::default();
+        for verdict in [
+            Verdict::Pass,
+            Verdict::Pass,
+            Verdict::Missing,
+            Verdict::Fail {
+                expected: "1".to_string(),
+                actual: "2".to_string(),
+            },
+        ] {
+            tally.add(&verdict);
+        }
+        assert_eq!("2 passed, 1 failed, 1 missing, 0 errors", tally.to_string());
+        assert!(tally.is_failure(false));
+        assert!(!tally.is_failure(true));
+
+        tally.add(&Verdict::Error);
+        assert!(tally.is_failure(true));
+    }
+
+    #[test]
+    fn test_examples() {
+        let res = examples(&Day1, &Part::ALL, None);
+
+        let names: Vec<&str> = res.iter().map(|run| run.example.name()).collect();
+        assert_eq!(vec!["day1-part1.txt", "day1-part2.txt"], names);
+        let parts: Vec<Vec<Part>> = res
+            .iter()
+            .map(|run| run.records.iter().map(|record| record.part).collect())
+            .collect();
+        assert_eq!(vec![vec![Part::One], vec![Part::Two]], parts);
+        assert!(res
+            .iter()
+            .flat_map(|run| &run.verdicts)
+            .all(|verdict| *verdict == Verdict::Pass));
+
+        assert!(examples(&Day1, &[Part::One], None)[1].records.is_empty());
+    }
+}
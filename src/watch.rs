@@ -0,0 +1,192 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use crate::{
+    bench::format_duration,
+    input::Source,
+    runner::{self, Options, Record},
+    solution::{DynSolution, Example, Part},
+    verify::{self, Verdict},
+};
+
+/// A file polled for changes through its metadata.
+#[derive(Debug)]
+pub struct Watched {
+    pub path: PathBuf,
+    stamp: Option<(SystemTime, u64)>,
+}
+
+impl Watched {
+    pub fn new(path: PathBuf) -> Self {
+        let stamp = stamp(&path);
+        Self { path, stamp }
+    }
+
+    /// Whether the file changed since the last poll, appearing and
+    /// disappearing count as changes.
+    pub fn poll(&mut self) -> bool {
+        let stamp = stamp(&self.path);
+        let changed = stamp != self.stamp;
+        self.stamp = stamp;
+        changed
+    }
+}
+
+// The length catches edits made within the timestamp resolution.
+fn stamp(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// `answer` next to the one from the previous run.
+pub fn against_previous(previous: Option<&str>, answer: &str) -> String {
+    match previous {
+        None => answer.to_string(),
+        Some(previous) if previous == answer => format!("{} (unchanged)", answer),
+        Some(previous) => format!("{} (was {})", answer, previous),
+    }
+}
+
+/// The input or one of the examples of the watched day.
+#[derive(Debug)]
+pub struct Target {
+    pub label: String,
+    pub file: Watched,
+    example: Option<&'static Example>,
+    previous: [Option<String>; 2],
+}
+
+impl Target {
+    pub fn input(path: PathBuf) -> Self {
+        Self {
+            label: path.display().to_string(),
+            file: Watched::new(path),
+            example: None,
+            previous: Default::default(),
+        }
+    }
+
+    pub fn example(example: &'static Example) -> Self {
+        Self {
+            label: format!("example {}", example.name()),
+            file: Watched::new(example.path.into()),
+            example: Some(example),
+            previous: Default::default(),
+        }
+    }
+
+    /// Runs `parts` on the file, for examples only the parts with an expected
+    /// answer. Returns the lines to show, each answer next to the previous
+    /// one, and the records for their errors.
+    pub fn run(
+        &mut self,
+        solution: &dyn DynSolution,
+        parts: &[Part],
+    ) -> (Vec<String>, Vec<Record>) {
+        let parts: Vec<Part> = match self.example {
+            Some(example) => parts
+                .iter()
+                .copied()
+                .filter(|&part| example.expected(part).is_some())
+                .collect(),
+            None => parts.to_vec(),
+        };
+        let records = runner::run_day(
+            solution,
+            &Source::File(self.file.path.clone()),
+            &parts,
+            &Options::default(),
+        );
+
+        let Some(first) = records.first() else {
+            return (Vec::new(), records);
+        };
+        let mut lines = vec![format!(
+            "{} (parse {})",
+            self.label,
+            format_duration(first.parse_time)
+        )];
+        for record in &records {
+            let previous = &mut self.previous[record.part.number() as usize - 1];
+            let Some(answer) = record.answer().map(|a| a.to_string()) else {
+                lines.push(format!("  part {}: failed", record.part));
+                continue;
+            };
+
+            let verdict = match self.example {
+                Some(example) => match verify::compare(example.expected(record.part), record) {
+                    Verdict::Fail { expected, .. } => format!(", FAIL expected {}", expected),
+                    verdict => format!(", {}", verdict),
+                },
+                None => String::new(),
+            };
+            lines.push(format!(
+                "  part {}: {} in {}{}",
+                record.part,
+                against_previous(previous.as_deref(), &answer),
+                format_duration(record.solve_time),
+                verdict
+            ));
+            *previous = Some(answer);
+        }
+        (lines, records)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+    use crate::advent23::day2::Day2;
+
+    #[test]
+    fn test_poll() {
+        let path = env::temp_dir().join(format!("aoc-watch-{}", std::process::id()));
+        let mut watched = Watched::new(path.clone());
+        assert!(!watched.poll());
+
+        fs::write(&path, "1").unwrap();
+        assert!(watched.poll());
+        assert!(!watched.poll());
+
+        fs::write(&path, "12").unwrap();
+        assert!(watched.poll());
+
+        fs::remove_file(&path).unwrap();
+        assert!(watched.poll());
+        assert!(!watched.poll());
+    }
+
+    #[test]
+    fn test_against_previous() {
+        assert_eq!("13", against_previous(None, "13"));
+        assert_eq!("13 (unchanged)", against_previous(Some("13"), "13"));
+        assert_eq!("13 (was 12)", against_previous(Some("12"), "13"));
+    }
+
+    #[test]
+    fn test_target_run() {
+        let path = env::temp_dir().join(format!("aoc-target-{}", std::process::id()));
+        fs::write(&path, "Game 1: 3 blue, 4 red\n").unwrap();
+        let mut target = Target::input(path.clone());
+
+        let (lines, records) = target.run(&Day2, &[Part::One]);
+        assert_eq!(1, records.len());
+        assert_eq!(2, lines.len());
+        assert!(lines[1].starts_with("  part 1: 1 in "));
+
+        fs::write(&path, "Game 2: 3 blue, 4 red\n").unwrap();
+        let (lines, _) = target.run(&Day2, &[Part::One]);
+        assert!(lines[1].starts_with("  part 1: 2 (was 1) in "));
+
+        fs::write(&path, "Game x: 3 blue\n").unwrap();
+        let (lines, records) = target.run(&Day2, &[Part::One]);
+        assert_eq!("  part 1: failed", lines[1]);
+        assert!(records[0].answer().is_none());
+        fs::remove_file(&path).unwrap();
+    }
+}